@@ -0,0 +1,327 @@
+use std::{
+    fmt::{Display as FmtDisplay, Formatter, Result as FmtResult},
+    path::Path,
+};
+
+use anstream::println;
+use clap::{Parser, ValueEnum};
+use indexmap::IndexMap;
+use owo_colors::OwoColorize;
+use proc_exit::Code;
+use serde::Serialize;
+use serde_json::{to_string, to_string_pretty};
+use tracing::{debug, instrument};
+
+use crate::{
+    config::Config,
+    error::{Result, exit},
+    schema::{SchemaOpt, spec::Spec},
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum DiffFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum DiffItem {
+    Metric,
+    Pageview,
+    Source,
+}
+
+impl FmtDisplay for DiffItem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(
+            f,
+            "{}",
+            to_string(self)
+                .expect("Failed to serialize DiffItem")
+                .trim_matches('"')
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "change")]
+pub(crate) enum ChangeKind {
+    Added,
+    Removed,
+    Renamed {
+        from: String,
+    },
+    DescriptionChanged {
+        old: Option<String>,
+        new: Option<String>,
+    },
+    SourcesAdded {
+        sources: Vec<String>,
+    },
+    SourcesRemoved {
+        sources: Vec<String>,
+    },
+}
+
+impl ChangeKind {
+    /// Whether the change breaks the code or the data of the consumers
+    fn is_breaking(&self) -> bool {
+        matches!(
+            self,
+            ChangeKind::Removed | ChangeKind::Renamed { .. } | ChangeKind::SourcesRemoved { .. }
+        )
+    }
+}
+
+impl FmtDisplay for ChangeKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            ChangeKind::Added => write!(f, "added"),
+            ChangeKind::Removed => write!(f, "removed"),
+            ChangeKind::Renamed { from } => write!(f, "renamed from `{from}`"),
+            ChangeKind::DescriptionChanged { .. } => write!(f, "description changed"),
+            ChangeKind::SourcesAdded { sources } => {
+                write!(f, "sources added: {}", sources.join(", "))
+            }
+            ChangeKind::SourcesRemoved { sources } => {
+                write!(f, "sources removed: {}", sources.join(", "))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct Change {
+    pub(crate) item: DiffItem,
+    pub(crate) name: String,
+    #[serde(flatten)]
+    pub(crate) kind: ChangeKind,
+    pub(crate) breaking: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct Report<'a> {
+    breaking: usize,
+    non_breaking: usize,
+    changes: &'a [Change],
+}
+
+/// Compare two versions of a plan
+#[derive(Debug, Parser)]
+pub struct Diff {
+    /// Old plan file or folder, or a git revision to read the plan at
+    pub old: String,
+
+    #[clap(flatten)]
+    pub schema: SchemaOpt,
+
+    /// Format of the report [default: text]
+    #[clap(long, value_enum)]
+    pub format: Option<DiffFormat>,
+
+    /// Exit with a non-zero code when there are breaking changes
+    #[clap(long)]
+    pub fail_on_breaking: bool,
+}
+
+impl Diff {
+    pub(crate) fn apply(&mut self, config: &Config) {
+        self.schema.apply(config);
+    }
+
+    #[instrument(name = "diff", skip_all)]
+    pub(crate) fn run(&self) -> Result {
+        let old = if Path::new(&self.old).exists() {
+            SchemaOpt {
+                plan: Some(self.old.clone().into()),
+                plan_flag: None,
+                ..self.schema.clone()
+            }
+            .load()?
+        } else {
+            debug!("Reading old plan at revision: {}", self.old);
+            self.schema.load_at(&self.old)?
+        };
+
+        let new = self.schema.load()?;
+
+        let changes = diff(&old, &new);
+        let breaking = changes.iter().filter(|c| c.breaking).count();
+
+        match self.format.unwrap_or_default() {
+            DiffFormat::Json => println!(
+                "{}",
+                to_string_pretty(&Report {
+                    breaking,
+                    non_breaking: changes.len() - breaking,
+                    changes: &changes,
+                })?
+            ),
+            DiffFormat::Text => print_text(&changes, breaking),
+        }
+
+        if breaking > 0 && self.fail_on_breaking {
+            exit(Code::FAILURE);
+        }
+
+        Ok(())
+    }
+}
+
+fn print_text(changes: &[Change], breaking: usize) {
+    let mut last = None;
+
+    for change in changes {
+        if last != Some((change.item, &change.name)) {
+            println!(
+                "\n{} {}",
+                change.name.blue(),
+                format!("({})", change.item).cyan()
+            );
+            last = Some((change.item, &change.name));
+        }
+
+        if change.breaking {
+            println!("  {} {}", "breaking".red(), change.kind);
+        } else {
+            println!("  {} {}", "    safe".green(), change.kind);
+        }
+    }
+
+    if !changes.is_empty() {
+        println!(
+            "\n{} breaking, {} non-breaking changes\n",
+            breaking.red().bold(),
+            (changes.len() - breaking).green().bold()
+        );
+    }
+}
+
+/// Description and sources of an item, whichever its kind
+type Fields<'a> = (Option<&'a String>, Option<&'a Vec<String>>);
+
+/// Compare the plans, listing the changes of metrics, pageviews and sources
+pub(crate) fn diff(old: &IndexMap<String, Spec>, new: &IndexMap<String, Spec>) -> Vec<Change> {
+    let mut changes = vec![];
+
+    for item in [DiffItem::Metric, DiffItem::Pageview, DiffItem::Source] {
+        changes.extend(diff_items(item, &items(old, item), &items(new, item)));
+    }
+
+    changes
+}
+
+/// Collect the items of a kind across the files of a plan
+fn items(files: &IndexMap<String, Spec>, item: DiffItem) -> IndexMap<&String, Fields<'_>> {
+    let mut items = IndexMap::new();
+
+    for spec in files.values() {
+        match item {
+            DiffItem::Metric => {
+                items.extend(spec.metrics.iter().flatten().map(|(name, event)| {
+                    (name, (event.description.as_ref(), event.sources.as_ref()))
+                }))
+            }
+            DiffItem::Pageview => {
+                items.extend(spec.pageviews.iter().flatten().map(|(name, pageview)| {
+                    (
+                        name,
+                        (pageview.description.as_ref(), pageview.sources.as_ref()),
+                    )
+                }))
+            }
+            DiffItem::Source => items.extend(
+                spec.sources
+                    .iter()
+                    .flatten()
+                    .map(|(name, source)| (name, (source.description.as_ref(), None))),
+            ),
+        }
+    }
+
+    items
+}
+
+fn diff_items(
+    item: DiffItem,
+    old: &IndexMap<&String, Fields<'_>>,
+    new: &IndexMap<&String, Fields<'_>>,
+) -> Vec<Change> {
+    let mut changes = vec![];
+
+    let mut removed = old
+        .iter()
+        .filter(|(name, _)| !new.contains_key(*name))
+        .collect::<IndexMap<_, _>>();
+
+    let mut push = |name: &str, kind: ChangeKind| {
+        changes.push(Change {
+            item,
+            name: name.to_string(),
+            breaking: kind.is_breaking(),
+            kind,
+        });
+    };
+
+    for (name, fields) in new {
+        let Some(old_fields) = old.get(name) else {
+            // An item with the same description and sources as a removed one
+            // is a rename, as long as it has a description to match on
+            let renamed = removed
+                .iter()
+                .find(|(_, old_fields)| fields.0.is_some() && **old_fields == fields)
+                .map(|(old_name, _)| **old_name);
+
+            match renamed {
+                Some(from) => {
+                    removed.shift_remove(&from);
+                    push(name, ChangeKind::Renamed { from: from.clone() });
+                }
+                None => push(name, ChangeKind::Added),
+            }
+
+            continue;
+        };
+
+        if old_fields.0 != fields.0 {
+            push(
+                name,
+                ChangeKind::DescriptionChanged {
+                    old: old_fields.0.cloned(),
+                    new: fields.0.cloned(),
+                },
+            );
+        }
+
+        let old_sources = old_fields.1.map(Vec::as_slice).unwrap_or_default();
+        let new_sources = fields.1.map(Vec::as_slice).unwrap_or_default();
+
+        let added = new_sources
+            .iter()
+            .filter(|s| !old_sources.contains(s))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let dropped = old_sources
+            .iter()
+            .filter(|s| !new_sources.contains(s))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if !added.is_empty() {
+            push(name, ChangeKind::SourcesAdded { sources: added });
+        }
+
+        if !dropped.is_empty() {
+            push(name, ChangeKind::SourcesRemoved { sources: dropped });
+        }
+    }
+
+    for name in removed.keys() {
+        push(name, ChangeKind::Removed);
+    }
+
+    changes
+}
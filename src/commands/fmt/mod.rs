@@ -0,0 +1,160 @@
+use std::{
+    fs::{read_to_string, write},
+    path::Path,
+};
+
+use anstream::println;
+use clap::Parser;
+use eyre::eyre;
+use owo_colors::OwoColorize;
+use proc_exit::Code;
+use serde_yml::{Mapping, Value};
+use tracing::{instrument, trace};
+
+use crate::{
+    commands::lint::rules::RulesConfig,
+    config::Config,
+    error::{Result, exit},
+    schema::{
+        SchemaOpt,
+        spec::{Event, Pageview, Source, Spec},
+        strict::fields,
+    },
+};
+
+use self::yaml::{Comments, key_name};
+
+pub(crate) mod yaml;
+
+/// Format plan files
+#[derive(Debug, Parser)]
+pub struct Fmt {
+    #[clap(flatten)]
+    pub schema: SchemaOpt,
+
+    /// Exit with a non-zero code if any file is not formatted, without writing
+    #[clap(long)]
+    pub check: bool,
+
+    /// Sort metrics and pageviews by name instead of keeping their order
+    #[clap(long, overrides_with = "no_sort")]
+    pub sort: bool,
+
+    /// Keep the order of metrics and pageviews, even when `ods.toml` sets
+    /// `sort`
+    #[clap(long, overrides_with = "sort")]
+    pub no_sort: bool,
+}
+
+impl Fmt {
+    pub(crate) fn apply(&mut self, config: &Config) {
+        self.schema.apply(config);
+
+        if !self.no_sort {
+            self.sort = self.sort || config.fmt.sort.unwrap_or_default();
+        }
+    }
+
+    #[instrument(name = "fmt", skip_all)]
+    pub(crate) fn run(&self) -> Result {
+        let mut unformatted = 0;
+
+        for (name, path) in self.schema.files()? {
+            trace!("Formatting file: {}", name);
+
+            let content = read_to_string(&path)?;
+            let formatted = format(&path, &content, self.sort)
+                .map_err(|e| eyre!("unable to format {name}: {e}"))?;
+
+            if formatted == content {
+                continue;
+            }
+
+            if self.check {
+                unformatted += 1;
+                println!("{} {}", "unformatted".red(), name);
+            } else {
+                write(&path, formatted)?;
+                println!("{} {}", "formatted".green(), name);
+            }
+        }
+
+        if unformatted > 0 {
+            exit(Code::FAILURE);
+        }
+
+        Ok(())
+    }
+}
+
+/// Format the content of a plan file, using the path to detect its format
+fn format(path: &Path, content: &str, sort: bool) -> Result<String> {
+    match path.extension().map(|v| v.to_string_lossy()) {
+        Some(v) if v == "yaml" || v == "yml" => {
+            let value = serde_yml::from_str(content)?;
+            let value = canonical(value, &mut vec![], sort);
+
+            Ok(yaml::to_string(&value, Comments::new(content)))
+        }
+        Some(v) if v == "json" => {
+            let value = serde_json::from_str(content)?;
+            let value = canonical(value, &mut vec![], sort);
+
+            Ok(format!("{}\n", serde_json::to_string_pretty(&value)?))
+        }
+        _ => Err(eyre!("unable to recognize file format")),
+    }
+}
+
+/// Known keys of the mapping at the path, in the order they are written
+fn key_order(path: &[String]) -> Option<Vec<&'static str>> {
+    match path {
+        [] => Some(fields::<Spec>().to_vec()),
+        [section] if section == "lint" => Some(fields::<RulesConfig>().to_vec()),
+        [section, rule] if section == "lint" => RulesConfig::rule_fields(rule),
+        [section, _] if section == "metrics" => Some(fields::<Event>().to_vec()),
+        [section, _] if section == "pageviews" => Some(fields::<Pageview>().to_vec()),
+        [section, _] if section == "sources" => Some(fields::<Source>().to_vec()),
+        _ => None,
+    }
+}
+
+/// Reorder the keys of the value into the canonical order
+fn canonical(value: Value, path: &mut Vec<String>, sort: bool) -> Value {
+    match value {
+        Value::Mapping(mapping) => {
+            let mut entries = mapping.into_iter().collect::<Vec<_>>();
+
+            if let Some(order) = key_order(path) {
+                // Unknown keys go after the known ones, keeping their order
+                entries.sort_by_key(|(key, _)| {
+                    key.as_str()
+                        .and_then(|key| order.iter().position(|o| *o == key))
+                        .unwrap_or(order.len())
+                });
+            } else if sort && matches!(path.as_slice(), [s] if s == "metrics" || s == "pageviews") {
+                entries.sort_by_cached_key(|(key, _)| {
+                    let name = key_name(key);
+                    (name.to_lowercase(), name)
+                });
+            }
+
+            entries
+                .into_iter()
+                .map(|(key, value)| {
+                    path.push(key_name(&key));
+                    let value = canonical(value, path, sort);
+                    path.pop();
+
+                    (key, value)
+                })
+                .collect::<Mapping>()
+                .into()
+        }
+        Value::Sequence(sequence) => sequence
+            .into_iter()
+            .map(|value| canonical(value, path, sort))
+            .collect(),
+        value => value,
+    }
+}
@@ -0,0 +1,304 @@
+use std::mem::take;
+
+use indexmap::IndexMap;
+use serde_yml::{Mapping, Value};
+
+use crate::commands::lsp::document::{is_list_item, parse_key, strip_comment};
+
+/// Comments of a YAML file keyed by the path of the key they belong to
+#[derive(Debug, Default)]
+pub(crate) struct Comments {
+    leading: IndexMap<Vec<String>, Vec<String>>,
+    trailing: IndexMap<Vec<String>, String>,
+    header: Vec<String>,
+    footer: Vec<String>,
+}
+
+impl Comments {
+    /// Collect the comments by scanning the lines of the file.
+    ///
+    /// Comments on their own line belong to the key following them and
+    /// comments at the end of a line belong to the key on that line. Comments
+    /// on list items are moved above the key holding the list. Comments at the
+    /// top of the file separated by a blank line stay at the top.
+    pub(crate) fn new(text: &str) -> Self {
+        let mut comments = Self::default();
+        let mut stack: Vec<(usize, String)> = vec![];
+        let mut pending = vec![];
+        let mut block_scalar = None;
+
+        for text in text.lines() {
+            // Lines of a block scalar are content even if they look like comments
+            if let Some(indent) = block_scalar {
+                let trimmed = text.trim_start();
+
+                if trimmed.is_empty() || text.len() - trimmed.len() > indent {
+                    continue;
+                }
+
+                block_scalar = None;
+            }
+
+            let content = strip_comment(text);
+            let comment = text[content.len()..].trim();
+            let trimmed = content.trim_start();
+            let indent = content.len() - trimmed.len();
+
+            if trimmed.trim().is_empty() {
+                if !comment.is_empty() {
+                    pending.push(comment.to_string());
+                } else if stack.is_empty() && comments.leading.is_empty() {
+                    comments.header.append(&mut pending);
+                }
+
+                continue;
+            }
+
+            let key = parse_key(trimmed);
+
+            while stack.last().is_some_and(|(i, _)| {
+                *i > indent || (*i == indent && (key.is_some() || !is_list_item(trimmed)))
+            }) {
+                stack.pop();
+            }
+
+            let mut path: Vec<String> = stack.iter().map(|(_, k)| k.clone()).collect();
+
+            match key {
+                Some((name, _, _, colon)) => {
+                    path.push(name.clone());
+
+                    comments
+                        .leading
+                        .entry(path.clone())
+                        .or_default()
+                        .append(&mut pending);
+
+                    if !comment.is_empty() {
+                        comments.trailing.insert(path, comment.to_string());
+                    }
+
+                    if trimmed[colon + 1..].trim_start().starts_with(['|', '>']) {
+                        block_scalar = Some(indent);
+                    }
+
+                    stack.push((indent, name));
+                }
+                None => {
+                    let leading = comments.leading.entry(path).or_default();
+                    leading.append(&mut pending);
+
+                    if !comment.is_empty() {
+                        leading.push(comment.to_string());
+                    }
+                }
+            }
+        }
+
+        comments.footer = pending;
+        comments
+    }
+}
+
+/// Write the value as block YAML, putting the comments back next to their keys
+pub(crate) fn to_string(value: &Value, comments: Comments) -> String {
+    let mut emitter = Emitter {
+        out: String::new(),
+        path: vec![],
+        comments,
+        inline: false,
+    };
+
+    for comment in take(&mut emitter.comments.header) {
+        emitter.line(0, &comment);
+    }
+
+    if !emitter.out.is_empty() {
+        emitter.out.push('\n');
+    }
+
+    match value {
+        Value::Mapping(mapping) => emitter.mapping(mapping, 0),
+        Value::Null => {}
+        value => emitter.line(0, &inline(value)),
+    }
+
+    // Comments whose keys no longer exist are kept at the end
+    let Comments {
+        leading,
+        trailing,
+        footer,
+        ..
+    } = take(&mut emitter.comments);
+
+    let rest = leading
+        .into_values()
+        .flatten()
+        .chain(trailing.into_values())
+        .chain(footer)
+        .collect::<Vec<_>>();
+
+    if !rest.is_empty() && !emitter.out.is_empty() {
+        emitter.out.push('\n');
+    }
+
+    for comment in rest {
+        emitter.line(0, &comment);
+    }
+
+    match emitter.out.trim_end() {
+        "" => String::new(),
+        out => format!("{out}\n"),
+    }
+}
+
+struct Emitter {
+    out: String,
+    path: Vec<String>,
+    comments: Comments,
+    /// Whether the next line continues a list item marker
+    inline: bool,
+}
+
+impl Emitter {
+    fn line(&mut self, indent: usize, text: &str) {
+        if !self.inline {
+            self.out.push_str(&" ".repeat(indent));
+        }
+
+        self.inline = false;
+        self.out.push_str(text);
+        self.out.push('\n');
+    }
+
+    fn mapping(&mut self, mapping: &Mapping, indent: usize) {
+        for (i, (key, value)) in mapping.iter().enumerate() {
+            self.path.push(key_name(key));
+
+            // Top level sections are separated by a blank line
+            if indent == 0 && i > 0 {
+                self.out.push('\n');
+            }
+
+            if !self.inline {
+                for comment in self
+                    .comments
+                    .leading
+                    .swap_remove(&self.path)
+                    .unwrap_or_default()
+                {
+                    self.line(indent, &comment);
+                }
+            }
+
+            let key = scalar(key, false);
+            let trailing = self
+                .comments
+                .trailing
+                .swap_remove(&self.path)
+                .map(|c| format!(" {c}"))
+                .unwrap_or_default();
+
+            match value {
+                Value::Mapping(mapping) if !mapping.is_empty() => {
+                    self.line(indent, &format!("{key}:{trailing}"));
+                    self.mapping(mapping, indent + 2);
+                }
+                Value::Sequence(sequence) if !sequence.iter().all(is_scalar) => {
+                    self.line(indent, &format!("{key}:{trailing}"));
+                    self.sequence(sequence, indent + 2);
+                }
+                Value::Null => self.line(indent, &format!("{key}:{trailing}")),
+                Value::String(text) if is_block(text) => {
+                    let (indicator, text) = match text.strip_suffix('\n') {
+                        Some(text) => ("|", text),
+                        None => ("|-", text.as_str()),
+                    };
+
+                    self.line(indent, &format!("{key}: {indicator}{trailing}"));
+
+                    for line in text.split('\n') {
+                        if line.is_empty() {
+                            self.out.push('\n');
+                        } else {
+                            self.line(indent + 2, line);
+                        }
+                    }
+                }
+                value => self.line(indent, &format!("{key}: {}{trailing}", inline(value))),
+            }
+
+            self.path.pop();
+        }
+    }
+
+    fn sequence(&mut self, sequence: &[Value], indent: usize) {
+        for value in sequence {
+            match value {
+                Value::Mapping(mapping) if !mapping.is_empty() => {
+                    self.out.push_str(&" ".repeat(indent));
+                    self.out.push_str("- ");
+                    self.inline = true;
+                    self.mapping(mapping, indent + 2);
+                }
+                value => self.line(indent, &format!("- {}", inline(value))),
+            }
+        }
+    }
+}
+
+fn is_scalar(value: &Value) -> bool {
+    !matches!(
+        value,
+        Value::Mapping(_) | Value::Sequence(_) | Value::Tagged(_)
+    )
+}
+
+/// Multi line strings are written as literal blocks unless their leading or
+/// trailing whitespace would be lost
+fn is_block(text: &str) -> bool {
+    text.trim_end_matches('\n').contains('\n')
+        && !text.starts_with([' ', '\n'])
+        && !text.ends_with("\n\n")
+}
+
+/// Write a value on a single line, using flow style for collections
+fn inline(value: &Value) -> String {
+    match value {
+        Value::Sequence(sequence) if sequence.iter().all(is_scalar) => format!(
+            "[{}]",
+            sequence
+                .iter()
+                .map(|v| scalar(v, true))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Value::Mapping(mapping) if mapping.is_empty() => "{}".to_string(),
+        value if is_scalar(value) => scalar(value, false),
+        value => serde_json::to_string(value).expect("Failed to serialize YAML value"),
+    }
+}
+
+/// Write a scalar, quoting it only when needed
+fn scalar(value: &Value, flow: bool) -> String {
+    let text = serde_yml::to_string(value).expect("Failed to serialize YAML value");
+    let text = text.trim_end();
+
+    match value {
+        Value::String(s)
+            if s.contains('\n')
+                || (flow && !text.starts_with('\'') && s.contains([',', '[', ']', '{', '}'])) =>
+        {
+            serde_json::to_string(s).expect("Failed to serialize string")
+        }
+        _ => text.to_string(),
+    }
+}
+
+/// Name of a key as it appears in the paths of the comments
+pub(super) fn key_name(key: &Value) -> String {
+    match key {
+        Value::String(key) => key.clone(),
+        key => scalar(key, false),
+    }
+}
@@ -0,0 +1,42 @@
+use std::path::Path;
+
+use csv::Writer;
+use eyre::eyre;
+use tracing::instrument;
+
+use crate::{
+    commands::generate::{catalog::Catalog, category, write},
+    error::Result,
+};
+
+/// Columns of the Amplitude Data taxonomy import
+const HEADERS: [&str; 5] = [
+    "Object Type",
+    "Object Name",
+    "Object Description",
+    "Event Category",
+    "Platform",
+];
+
+/// Write the metrics and pageviews as an Amplitude Data taxonomy CSV
+#[instrument(name = "amplitude_csv", skip_all)]
+pub(crate) fn generate(catalog: &Catalog, output: &Path) -> Result {
+    let mut writer = Writer::from_writer(vec![]);
+    writer.write_record(HEADERS)?;
+
+    for event in &catalog.events {
+        writer.write_record([
+            "Event",
+            event.name,
+            event.description.map(str::trim).unwrap_or_default(),
+            category(event.file),
+            &event.sources.join(", "),
+        ])?;
+    }
+
+    let content = writer
+        .into_inner()
+        .map_err(|e| eyre!("unable to write the CSV: {e}"))?;
+
+    write(&output.join("amplitude.csv"), &String::from_utf8(content)?)
+}
@@ -0,0 +1,83 @@
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::{Value, json, to_string_pretty};
+use tracing::instrument;
+
+use crate::{
+    commands::{
+        generate::{catalog::Catalog, sql::COLUMNS, write},
+        lint::rules::uses_name_case::Case,
+    },
+    error::Result,
+};
+
+#[derive(Debug, Serialize)]
+struct Record<'a> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    name: String,
+    namespace: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    doc: Option<&'a str>,
+    fields: Vec<Field>,
+}
+
+#[derive(Debug, Serialize)]
+struct Field {
+    name: &'static str,
+    doc: &'static str,
+    #[serde(rename = "type")]
+    kind: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default: Option<Value>,
+}
+
+/// Write an Avro record schema per metric and pageview
+#[instrument(name = "avro", skip_all)]
+pub(crate) fn generate(catalog: &Catalog, namespace: &str, output: &Path) -> Result {
+    for (event, name) in catalog
+        .events
+        .iter()
+        .zip(catalog.type_names("Avro record")?)
+    {
+        let fields = COLUMNS
+            .iter()
+            .map(|(name, doc, _)| {
+                let (kind, default) = match *name {
+                    "event_id" => (json!({"type": "string", "logicalType": "uuid"}), None),
+                    "timestamp" => (
+                        json!({"type": "long", "logicalType": "timestamp-millis"}),
+                        None,
+                    ),
+                    "user_id" => (json!(["null", "string"]), Some(Value::Null)),
+                    _ => (json!("string"), None),
+                };
+
+                Field {
+                    name,
+                    doc,
+                    kind,
+                    default,
+                }
+            })
+            .collect();
+
+        let record = Record {
+            kind: "record",
+            name,
+            namespace,
+            doc: event.description.map(str::trim),
+            fields,
+        };
+
+        write(
+            &output
+                .join("avro")
+                .join(format!("{}.avsc", Case::SnakeCase.convert(event.name))),
+            &format!("{}\n", to_string_pretty(&record)?),
+        )?;
+    }
+
+    Ok(())
+}
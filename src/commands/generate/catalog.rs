@@ -0,0 +1,172 @@
+use std::collections::HashSet;
+
+use eyre::eyre;
+use indexmap::IndexMap;
+use serde::Serialize;
+
+use crate::{commands::lint::rules::uses_name_case::Case, error::Result, schema::spec::Spec};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum EventKind {
+    Metric,
+    Pageview,
+}
+
+impl EventKind {
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            EventKind::Metric => "metric",
+            EventKind::Pageview => "pageview",
+        }
+    }
+}
+
+/// Metric or pageview sent by the sources
+#[derive(Debug, Serialize)]
+pub(crate) struct Event<'a> {
+    pub(crate) kind: EventKind,
+    pub(crate) file: &'a str,
+    pub(crate) name: &'a str,
+    pub(crate) description: Option<&'a str>,
+    pub(crate) sources: &'a [String],
+    #[serde(skip)]
+    pub(crate) anchor: String,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct Source<'a> {
+    pub(crate) file: &'a str,
+    pub(crate) name: &'a str,
+    pub(crate) description: Option<&'a str>,
+    #[serde(skip)]
+    pub(crate) anchor: String,
+}
+
+/// Items of the whole plan in the order of the files, which is what the
+/// generators work from
+#[derive(Debug, Serialize)]
+pub(crate) struct Catalog<'a> {
+    pub(crate) events: Vec<Event<'a>>,
+    pub(crate) sources: Vec<Source<'a>>,
+}
+
+impl<'a> Catalog<'a> {
+    pub(crate) fn new(files: &'a IndexMap<String, Spec>) -> Self {
+        let mut events = vec![];
+        let mut sources = vec![];
+
+        for (file, spec) in files {
+            for (name, metric) in spec.metrics.iter().flatten() {
+                events.push(Event {
+                    kind: EventKind::Metric,
+                    file,
+                    name,
+                    description: metric.description.as_deref(),
+                    sources: metric.sources.as_deref().unwrap_or_default(),
+                    anchor: anchor("metric", name),
+                });
+            }
+
+            for (name, pageview) in spec.pageviews.iter().flatten() {
+                events.push(Event {
+                    kind: EventKind::Pageview,
+                    file,
+                    name,
+                    description: pageview.description.as_deref(),
+                    sources: pageview.sources.as_deref().unwrap_or_default(),
+                    anchor: anchor("pageview", name),
+                });
+            }
+
+            for (name, source) in spec.sources.iter().flatten() {
+                sources.push(Source {
+                    file,
+                    name,
+                    description: source.description.as_deref(),
+                    anchor: anchor("source", name),
+                });
+            }
+        }
+
+        Self { events, sources }
+    }
+
+    /// Names of the declared sources followed by the ones only used by events
+    pub(crate) fn source_names(&self) -> Vec<&'a str> {
+        let mut names = self.sources.iter().map(|s| s.name).collect::<Vec<_>>();
+
+        for event in &self.events {
+            for source in event.sources {
+                if !names.contains(&source.as_str()) {
+                    names.push(source);
+                }
+            }
+        }
+
+        names
+    }
+
+    pub(crate) fn source(&self, name: &str) -> Option<&Source<'a>> {
+        self.sources.iter().find(|s| s.name == name)
+    }
+
+    /// PascalCase type name of each event for the code generators, which
+    /// must be an ASCII identifier unique across the plan
+    pub(crate) fn type_names(&self, target: &str) -> Result<Vec<String>> {
+        let mut seen = HashSet::new();
+
+        self.events
+            .iter()
+            .map(|event| {
+                let name = Case::PascalCase.convert(event.name);
+
+                // Avro and Protobuf names are ASCII only
+                if !name.starts_with(|c: char| c.is_ascii_alphabetic())
+                    || !name.chars().all(|c| c.is_ascii_alphanumeric())
+                {
+                    return Err(eyre!(
+                        "`{}` doesn't make a valid {target} name, use ASCII letters and digits starting with a letter",
+                        event.name
+                    ));
+                }
+
+                if !seen.insert(name.clone()) {
+                    return Err(eyre!(
+                        "`{}` has the same {target} name `{name}` as another event",
+                        event.name
+                    ));
+                }
+
+                Ok(name)
+            })
+            .collect()
+    }
+}
+
+/// Anchor of an item which only depends on its kind and name, so that links
+/// keep working when the plan is reorganized
+pub(crate) fn anchor(kind: &str, name: &str) -> String {
+    format!("{kind}-{}", slug(name))
+}
+
+/// Lowercase alphanumeric words of the name joined with dashes
+pub(crate) fn slug(name: &str) -> String {
+    let mut slug = String::new();
+    let mut dash = false;
+
+    for c in name.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            if dash && !slug.is_empty() {
+                slug.push('-');
+            }
+
+            slug.push(c);
+            dash = false;
+        } else {
+            dash = true;
+        }
+    }
+
+    slug
+}
@@ -0,0 +1,150 @@
+use std::{fmt::Write, path::Path};
+
+use clap::Parser;
+use serde::Deserialize;
+use serde_yml::{Mapping, Value};
+use tracing::instrument;
+
+use crate::{
+    commands::{
+        fmt::yaml::{self, Comments},
+        generate::{
+            catalog::{Catalog, Event},
+            sql::{COLUMNS, SqlOpt},
+            write,
+        },
+    },
+    error::Result,
+};
+
+#[derive(Debug, Clone, Default, Parser, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DbtOpt {
+    /// Name of the dbt source holding the event tables [default: events]
+    #[clap(long = "dbt-source")]
+    pub source: Option<String>,
+
+    /// Schema of the event tables, when it differs from the source name
+    #[clap(long = "dbt-schema")]
+    pub schema: Option<String>,
+}
+
+impl DbtOpt {
+    pub(crate) fn apply(&mut self, config: &DbtOpt) {
+        self.source = self.source.take().or(config.source.clone());
+        self.schema = self.schema.take().or(config.schema.clone());
+    }
+}
+
+/// Write a dbt source declaring the event tables along with a staging model
+/// per table. Tables are named like the `sql` format names them, and their
+/// [`COLUMNS`] are documented with fixed descriptions.
+#[instrument(name = "dbt", skip_all)]
+pub(crate) fn generate(catalog: &Catalog, options: &DbtOpt, sql: &SqlOpt, output: &Path) -> Result {
+    let source = options.source.as_deref().unwrap_or("events");
+    let dir = output.join("models").join("staging");
+
+    let names = sql.tables(catalog)?;
+    let tables = catalog
+        .events
+        .iter()
+        .zip(&names)
+        .map(|(event, name)| table(name, event))
+        .collect::<Vec<_>>();
+
+    let mut declaration = Mapping::new();
+    declaration.insert("name".into(), source.into());
+
+    if let Some(schema) = &options.schema {
+        declaration.insert("schema".into(), schema.as_str().into());
+    }
+
+    declaration.insert("tables".into(), tables.into());
+
+    let mut sources = Mapping::new();
+    sources.insert("version".into(), 2.into());
+    sources.insert("sources".into(), vec![Value::from(declaration)].into());
+
+    write(
+        &dir.join("sources.yml"),
+        &yaml::to_string(&sources.into(), Comments::default()),
+    )?;
+
+    for (event, table) in catalog.events.iter().zip(&names) {
+        write(
+            &dir.join(format!("stg_{source}__{table}.sql")),
+            &model(source, table, event),
+        )?;
+    }
+
+    Ok(())
+}
+
+fn table(name: &str, event: &Event) -> Value {
+    let columns = COLUMNS
+        .iter()
+        .map(|(column, description, _)| {
+            let mut mapping = Mapping::new();
+            mapping.insert("name".into(), (*column).into());
+            mapping.insert("description".into(), (*description).into());
+
+            match *column {
+                "event_id" => {
+                    mapping.insert("data_tests".into(), vec!["unique", "not_null"].into());
+                }
+                "timestamp" => {
+                    mapping.insert("data_tests".into(), vec!["not_null"].into());
+                }
+                "source" if !event.sources.is_empty() => {
+                    let mut values = Mapping::new();
+                    values.insert("values".into(), event.sources.to_vec().into());
+
+                    let mut test = Mapping::new();
+                    test.insert("accepted_values".into(), values.into());
+
+                    mapping.insert("data_tests".into(), vec![Value::from(test)].into());
+                }
+                _ => {}
+            }
+
+            Value::from(mapping)
+        })
+        .collect::<Vec<_>>();
+
+    let mut table = Mapping::new();
+    table.insert("name".into(), name.into());
+
+    if let Some(description) = event.description.map(str::trim) {
+        table.insert("description".into(), description.into());
+    }
+
+    table.insert("columns".into(), columns.into());
+    table.into()
+}
+
+fn model(source: &str, table: &str, event: &Event) -> String {
+    let columns = COLUMNS
+        .iter()
+        .map(|(column, _, _)| format!("    {column}"))
+        .collect::<Vec<_>>();
+
+    let mut out = format!(
+        "-- Staging model of the {} {}\n\n",
+        event.kind.name(),
+        event.name
+    );
+    let _ = writeln!(
+        out,
+        "select\n{}\nfrom {{{{ source({}, {}) }}}}",
+        columns.join(",\n"),
+        string(source),
+        string(table)
+    );
+
+    out
+}
+
+/// Jinja string literal, escaping the quotes which would end it early
+fn string(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+}
@@ -0,0 +1,183 @@
+use std::{collections::HashSet, fmt::Write, path::Path};
+
+use eyre::eyre;
+use tracing::instrument;
+
+use crate::{
+    commands::{
+        generate::{
+            catalog::{Catalog, Event},
+            sql::COLUMNS,
+            write,
+        },
+        lint::rules::uses_name_case::Case,
+    },
+    error::Result,
+};
+
+const INTERFACES: &str = "// Event is any event of the plan
+type Event interface {
+	// EventName is the name of the event in the plan
+	EventName() string
+}
+
+// Tracker sends events, implemented by the analytics client
+type Tracker interface {
+	Track(event Event) error
+}
+";
+
+/// Write a struct per metric and pageview to `events.go`, along with a
+/// `Tracker` interface sending any of them. The package name is the last part
+/// of the generator package.
+#[instrument(name = "go", skip_all)]
+pub(crate) fn generate(catalog: &Catalog, package: &str, output: &Path) -> Result {
+    let names = catalog.type_names("Go type")?;
+    let last = package.rsplit('.').next().unwrap_or(package);
+    let package = last
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .flat_map(|c| c.to_lowercase())
+        .collect::<String>();
+
+    if !package.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return Err(eyre!(
+            "`{last}` doesn't make a valid Go package name, use ASCII letters and digits starting with a letter"
+        ));
+    }
+
+    let mut structs = String::new();
+
+    for (event, name) in catalog.events.iter().zip(&names) {
+        structs.push('\n');
+        structure(&mut structs, name, event)?;
+    }
+
+    let mut out = format!(
+        "// Code generated by ods from the data plan. DO NOT EDIT.\n\npackage {package}\n\n"
+    );
+
+    // Go rejects unused imports, as in a plan without events
+    if structs.contains("time.Time") {
+        out.push_str("import \"time\"\n\n");
+    }
+
+    out.push_str(INTERFACES);
+    out.push_str(&structs);
+
+    write(&output.join("go").join("events.go"), &out)
+}
+
+fn structure(out: &mut String, name: &str, event: &Event) -> Result {
+    let source = match event.sources.is_empty() {
+        true => "string".to_string(),
+        false => format!("{name}Source"),
+    };
+
+    if !event.sources.is_empty() {
+        let _ = writeln!(
+            out,
+            "// {source} is a source which sends {name}\ntype {source} string\n"
+        );
+
+        let mut seen = HashSet::new();
+        let constants = event
+            .sources
+            .iter()
+            .map(|s| {
+                let suffix = Case::PascalCase.convert(s);
+
+                if suffix.is_empty() || !suffix.chars().all(|c| c.is_ascii_alphanumeric()) {
+                    return Err(eyre!(
+                        "source `{s}` of `{}` doesn't make a valid Go constant name, use ASCII letters and digits",
+                        event.name
+                    ));
+                }
+
+                let constant = format!("{source}{suffix}");
+
+                if !seen.insert(constant.clone()) {
+                    return Err(eyre!(
+                        "source `{s}` of `{}` has the same Go constant name `{constant}` as another source",
+                        event.name
+                    ));
+                }
+
+                Ok((constant, s))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let width = constants.iter().map(|(c, _)| c.len()).max().unwrap_or(0);
+
+        out.push_str("const (\n");
+
+        for (constant, value) in constants {
+            let _ = writeln!(out, "\t{constant:width$} {source} = {}", string(value));
+        }
+
+        out.push_str(")\n\n");
+    }
+
+    let _ = writeln!(
+        out,
+        "// {name} is the {} {}",
+        event.kind.name(),
+        string(event.name)
+    );
+
+    if let Some(description) = event.description {
+        out.push_str("//\n");
+
+        for line in description.trim().lines() {
+            let _ = writeln!(out, "// {line}");
+        }
+    }
+
+    let fields = COLUMNS
+        .iter()
+        .map(|(column, description, _)| {
+            let kind = match *column {
+                "timestamp" => "time.Time",
+                "source" => &source,
+                "user_id" => "*string",
+                _ => "string",
+            };
+            let tag = match *column {
+                "user_id" => format!("`json:\"{column},omitempty\"`"),
+                _ => format!("`json:\"{column}\"`"),
+            };
+            let field = match *column {
+                "event_id" => "EventID".to_string(),
+                "user_id" => "UserID".to_string(),
+                _ => Case::PascalCase.convert(column),
+            };
+
+            (field, kind.to_string(), tag, description)
+        })
+        .collect::<Vec<_>>();
+
+    let _ = writeln!(out, "type {name} struct {{");
+
+    for (index, (field, kind, tag, description)) in fields.iter().enumerate() {
+        if index > 0 {
+            out.push('\n');
+        }
+
+        let _ = writeln!(out, "\t// {description}");
+        let _ = writeln!(out, "\t{field} {kind} {tag}");
+    }
+
+    out.push_str("}\n\n");
+
+    let _ = writeln!(
+        out,
+        "// EventName is the name of the event in the plan\nfunc ({name}) EventName() string {{ return {} }}",
+        string(event.name)
+    );
+
+    Ok(())
+}
+
+/// Go string literal, which JSON strings are valid as
+fn string(value: &str) -> String {
+    serde_json::Value::from(value).to_string()
+}
@@ -0,0 +1,50 @@
+(function () {
+  const items = JSON.parse(document.getElementById("items").textContent);
+  const search = document.getElementById("search");
+  const sources = Array.from(document.querySelectorAll("input[name=source]"));
+  const list = document.getElementById("list");
+  const count = document.getElementById("count");
+
+  function render() {
+    const query = search.value.trim().toLowerCase();
+    const selected = sources.filter((s) => s.checked).map((s) => s.value);
+
+    const matching = items.filter(
+      (item) =>
+        (!query ||
+          item.name.toLowerCase().includes(query) ||
+          (item.description || "").toLowerCase().includes(query)) &&
+        (!selected.length ||
+          (item.kind === "source"
+            ? selected.includes(item.name)
+            : item.sources.some((s) => selected.includes(s)))),
+    );
+
+    list.replaceChildren(
+      ...matching.map((item) => {
+        const li = document.createElement("li");
+        const link = document.createElement("a");
+        link.href = item.url;
+        link.textContent = item.name;
+        const kind = document.createElement("span");
+        kind.className = "kind";
+        kind.textContent = item.kind;
+        li.append(link, kind);
+
+        if (item.description) {
+          const description = document.createElement("p");
+          description.textContent = item.description;
+          li.append(description);
+        }
+
+        return li;
+      }),
+    );
+
+    count.textContent = matching.length + " of " + items.length + " items";
+  }
+
+  search.addEventListener("input", render);
+  sources.forEach((s) => s.addEventListener("change", render));
+  render();
+})();
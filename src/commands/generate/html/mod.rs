@@ -0,0 +1,261 @@
+use std::{fmt::Write, path::Path};
+
+use eyre::eyre;
+use indexmap::IndexMap;
+use serde::Serialize;
+use tracing::instrument;
+
+use crate::{
+    commands::generate::{
+        catalog::{Catalog, Event, slug},
+        write,
+    },
+    error::Result,
+};
+
+const STYLE: &str = include_str!("style.css");
+const SCRIPT: &str = include_str!("explorer.js");
+
+/// Entry of the search index embedded in the explorer page
+#[derive(Debug, Serialize)]
+struct Item<'a> {
+    kind: &'static str,
+    name: &'a str,
+    description: Option<&'a str>,
+    sources: &'a [String],
+    url: String,
+}
+
+/// Write a self-contained site to explore the plan to the output folder
+#[instrument(name = "html", skip_all)]
+pub(crate) fn generate(catalog: &Catalog, output: &Path) -> Result {
+    for (path, content) in render(catalog)? {
+        write(&output.join(path), &content)?;
+    }
+
+    Ok(())
+}
+
+/// Pages of the site, by their path relative to the root
+pub(crate) fn render(catalog: &Catalog) -> Result<IndexMap<String, String>> {
+    let sources = catalog.source_names();
+
+    let mut items = catalog
+        .events
+        .iter()
+        .map(|event| Item {
+            kind: event.kind.name(),
+            name: event.name,
+            description: event.description.map(str::trim),
+            sources: event.sources,
+            url: event_page(event),
+        })
+        .collect::<Vec<_>>();
+
+    items.extend(sources.iter().map(|name| {
+        Item {
+            kind: "source",
+            name,
+            description: catalog
+                .source(name)
+                .and_then(|s| s.description)
+                .map(str::trim),
+            sources: &[],
+            url: source_page(name),
+        }
+    }));
+
+    let mut pages = IndexMap::new();
+    pages.insert("index.html".to_string(), render_index(&items, &sources)?);
+
+    // Names only differing in case or punctuation share a page
+    for event in &catalog.events {
+        let path = event_page(event);
+
+        if pages.insert(path.clone(), render_event(event)).is_some() {
+            return Err(eyre!(
+                "`{}` has the same page `{path}` as another {}",
+                event.name,
+                event.kind.name()
+            ));
+        }
+    }
+
+    for name in &sources {
+        let path = source_page(name);
+
+        if pages
+            .insert(path.clone(), render_source(catalog, name))
+            .is_some()
+        {
+            return Err(eyre!(
+                "`{name}` has the same page `{path}` as another source"
+            ));
+        }
+    }
+
+    Ok(pages)
+}
+
+/// Path of the page of an event relative to the output, which only depends on
+/// its kind and name
+fn event_page(event: &Event) -> String {
+    format!("{}s/{}.html", event.kind.name(), slug(event.name))
+}
+
+fn source_page(name: &str) -> String {
+    format!("sources/{}.html", slug(name))
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Wrap the body in a page, with links relative to the folder of the page
+fn layout(title: &str, root: &str, body: &str) -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{title}</title>
+<style>
+{STYLE}</style>
+</head>
+<body>
+<header><a href="{root}index.html">Data plan</a></header>
+<main>
+{body}</main>
+</body>
+</html>
+"#,
+        title = escape(title),
+    )
+}
+
+fn render_index(items: &[Item], sources: &[&str]) -> Result<String> {
+    let mut body = String::from(
+        "<h1>Data plan</h1>\n<div class=\"filters\">\n\
+         <input id=\"search\" type=\"search\" placeholder=\"Search names and descriptions\" autofocus>\n",
+    );
+
+    for source in sources {
+        let _ = writeln!(
+            body,
+            "<label><input type=\"checkbox\" name=\"source\" value=\"{0}\"> {0}</label>",
+            escape(source)
+        );
+    }
+
+    body.push_str("</div>\n<p id=\"count\"></p>\n<ul id=\"list\" class=\"items\">\n");
+
+    // Listed without the script too, which then takes over the filtering
+    for item in items {
+        let _ = write!(
+            body,
+            "<li><a href=\"{}\">{}</a><span class=\"kind\">{}</span>",
+            escape(&item.url),
+            escape(item.name),
+            item.kind
+        );
+
+        if let Some(description) = item.description {
+            let _ = write!(body, "<p>{}</p>", escape(description.trim()));
+        }
+
+        body.push_str("</li>\n");
+    }
+
+    // `</` would end the script element early
+    let index = serde_json::to_string(items)?.replace("</", "<\\/");
+
+    let _ = write!(
+        body,
+        "</ul>\n<script id=\"items\" type=\"application/json\">{index}</script>\n<script>\n{SCRIPT}</script>\n"
+    );
+
+    Ok(layout("Data plan", "", &body))
+}
+
+fn description(body: &mut String, description: Option<&str>) {
+    if let Some(description) = description {
+        for paragraph in description.trim().split("\n\n") {
+            let _ = writeln!(body, "<p>{}</p>", escape(paragraph));
+        }
+    }
+}
+
+fn render_event(event: &Event) -> String {
+    let mut body = format!(
+        "<h1>{}<span class=\"kind\">{}</span></h1>\n",
+        escape(event.name),
+        event.kind.name()
+    );
+
+    description(&mut body, event.description);
+
+    let _ = write!(
+        body,
+        "<dl>\n<dt>Defined in</dt>\n<dd><code>{}</code></dd>\n<dt>Sources</dt>\n<dd>",
+        escape(event.file)
+    );
+
+    if event.sources.is_empty() {
+        body.push_str("none");
+    }
+
+    let sources = event
+        .sources
+        .iter()
+        .map(|s| format!("<a href=\"../{}\">{}</a>", source_page(s), escape(s)))
+        .collect::<Vec<_>>();
+
+    let _ = writeln!(body, "{}</dd>\n</dl>", sources.join(", "));
+
+    layout(event.name, "../", &body)
+}
+
+fn render_source(catalog: &Catalog, name: &str) -> String {
+    let source = catalog.source(name);
+
+    let mut body = format!(
+        "<h1>{}<span class=\"kind\">source</span></h1>\n",
+        escape(name)
+    );
+
+    description(&mut body, source.and_then(|s| s.description));
+
+    body.push_str("<dl>\n<dt>Defined in</dt>\n<dd>");
+
+    match source {
+        Some(source) => {
+            let _ = write!(body, "<code>{}</code>", escape(source.file));
+        }
+        None => body.push_str("not declared in the plan"),
+    }
+
+    body.push_str("</dd>\n</dl>\n<h2>Events</h2>\n<ul class=\"items\">\n");
+
+    for event in catalog
+        .events
+        .iter()
+        .filter(|e| e.sources.iter().any(|s| s == name))
+    {
+        let _ = writeln!(
+            body,
+            "<li><a href=\"../{}\">{}</a><span class=\"kind\">{}</span></li>",
+            event_page(event),
+            escape(event.name),
+            event.kind.name()
+        );
+    }
+
+    body.push_str("</ul>\n");
+
+    layout(name, "../", &body)
+}
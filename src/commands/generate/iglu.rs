@@ -0,0 +1,275 @@
+use std::{
+    collections::HashSet,
+    fmt::{Display as FmtDisplay, Formatter, Result as FmtResult},
+    fs::{read_dir, read_to_string},
+    path::{Path, PathBuf},
+};
+
+use clap::Parser;
+use eyre::eyre;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, to_string_pretty, to_value};
+use tracing::{debug, instrument, trace};
+
+use crate::{
+    commands::generate::{
+        catalog::{Catalog, slug},
+        write,
+    },
+    error::Result,
+};
+
+/// Meta-schema of self-describing JSON schemas
+const META_SCHEMA: &str =
+    "http://iglucentral.com/schemas/com.snowplowanalytics.self-desc/schema/jsonschema/1-0-0#";
+
+#[derive(Debug, Clone, Default, Parser, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct IgluOpt {
+    /// Vendor of the Iglu schemas, usually a reversed domain like `com.acme`
+    #[clap(long = "iglu-vendor")]
+    pub vendor: Option<String>,
+
+    /// Earlier output to compare with when versioning the Iglu schemas
+    /// [default: the output folder]
+    #[clap(long = "iglu-previous")]
+    pub previous: Option<PathBuf>,
+}
+
+impl IgluOpt {
+    pub(crate) fn apply(&mut self, config: &IgluOpt) {
+        self.vendor = self.vendor.take().or(config.vendor.clone());
+        self.previous = self.previous.take().or(config.previous.clone());
+    }
+}
+
+/// SchemaVer version, as `MODEL-REVISION-ADDITION`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct SchemaVer(u32, u32, u32);
+
+impl SchemaVer {
+    const INITIAL: Self = Self(1, 0, 0);
+
+    fn parse(version: &str) -> Option<Self> {
+        let mut parts = version.split('-').map(|p| p.parse().ok());
+
+        match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(Some(model)), Some(Some(revision)), Some(Some(addition)), None) => {
+                Some(Self(model, revision, addition))
+            }
+            _ => None,
+        }
+    }
+
+    fn bump(self, change: Change) -> Self {
+        match change {
+            Change::Model => Self(self.0 + 1, 0, 0),
+            Change::Revision => Self(self.0, self.1 + 1, 0),
+            Change::Addition => Self(self.0, self.1, self.2 + 1),
+        }
+    }
+}
+
+impl FmtDisplay for SchemaVer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}-{}-{}", self.0, self.1, self.2)
+    }
+}
+
+/// Part of the version to bump for a change, following SchemaVer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Change {
+    /// Rejects all the data written against the previous version
+    Model,
+    /// May reject some of the data written against the previous version
+    Revision,
+    /// Accepts all the data written against the previous version
+    Addition,
+}
+
+#[derive(Debug, Serialize)]
+struct Schema<'a> {
+    #[serde(rename = "$schema")]
+    meta_schema: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<&'a str>,
+    #[serde(rename = "self")]
+    self_describing: SelfDescribing<'a>,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    properties: Map<String, Value>,
+    #[serde(rename = "additionalProperties")]
+    additional_properties: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct SelfDescribing<'a> {
+    vendor: &'a str,
+    name: String,
+    format: &'static str,
+    version: String,
+}
+
+/// Write a self-describing JSON schema per metric and pageview, in the layout
+/// of an Iglu repository
+#[instrument(name = "iglu", skip_all)]
+pub(crate) fn generate(catalog: &Catalog, options: &IgluOpt, output: &Path) -> Result {
+    let vendor = options.vendor.as_deref().ok_or(eyre!(
+        "the iglu format needs a vendor, pass --iglu-vendor or set `vendor` under [generate.iglu] in ods.toml"
+    ))?;
+
+    if vendor.is_empty()
+        || !vendor
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || ['.', '-', '_'].contains(&c))
+    {
+        return Err(eyre!("invalid Iglu vendor `{vendor}`"));
+    }
+
+    let previous = options.previous.as_deref().unwrap_or(output);
+    let mut names = HashSet::new();
+
+    for event in &catalog.events {
+        let name = slug(event.name).replace('-', "_");
+
+        // Iglu names are ASCII only, while slugs keep any letter
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(eyre!(
+                "`{}` doesn't make a valid Iglu schema name, use ASCII letters and digits",
+                event.name
+            ));
+        }
+
+        if !names.insert(name.clone()) {
+            return Err(eyre!(
+                "`{}` has the same Iglu schema name `{name}` as another event",
+                event.name
+            ));
+        }
+
+        let dir = Path::new("schemas")
+            .join(vendor)
+            .join(&name)
+            .join("jsonschema");
+
+        let mut schema = Schema {
+            meta_schema: META_SCHEMA,
+            description: event.description.map(str::trim),
+            self_describing: SelfDescribing {
+                vendor,
+                name,
+                format: "jsonschema",
+                version: SchemaVer::INITIAL.to_string(),
+            },
+            kind: "object",
+            properties: Map::new(),
+            additional_properties: false,
+        };
+
+        let version = match latest(&previous.join(&dir))? {
+            Some((version, old)) => match classify(&old, &to_value(&schema)?) {
+                Some(change) => {
+                    debug!("{} changed: {:?}", event.name, change);
+                    version.bump(change)
+                }
+                None => version,
+            },
+            None => SchemaVer::INITIAL,
+        };
+
+        trace!("Writing {} at {}", event.name, version);
+        schema.self_describing.version = version.to_string();
+
+        write(
+            &output.join(dir).join(version.to_string()),
+            &format!("{}\n", to_string_pretty(&schema)?),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Latest version of a schema in a folder along with its content
+fn latest(dir: &Path) -> Result<Option<(SchemaVer, Value)>> {
+    if !dir.is_dir() {
+        return Ok(None);
+    }
+
+    let mut versions = vec![];
+
+    for entry in read_dir(dir)? {
+        let path = entry?.path();
+
+        if let Some(version) = path
+            .file_name()
+            .and_then(|name| SchemaVer::parse(&name.to_string_lossy()))
+        {
+            versions.push((version, path));
+        }
+    }
+
+    let Some((version, path)) = versions.into_iter().max() else {
+        return Ok(None);
+    };
+
+    let content = read_to_string(&path)?;
+    let schema = serde_json::from_str(&content)
+        .map_err(|e| eyre!("unable to parse {}: {e}", path.display()))?;
+
+    Ok(Some((version, schema)))
+}
+
+/// How a schema changed from the previous version, if it did at all
+fn classify(old: &Value, new: &Value) -> Option<Change> {
+    let strip = |schema: &Value| {
+        let mut schema = schema.clone();
+        if let Some(schema) = schema.as_object_mut() {
+            schema.remove("self");
+        }
+        schema
+    };
+
+    let (old, new) = (strip(old), strip(new));
+    if old == new {
+        return None;
+    }
+
+    let properties = |schema: &Value| schema.get("properties").cloned().unwrap_or_default();
+    let required = |schema: &Value| {
+        schema
+            .get("required")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default()
+    };
+    let closed = |schema: &Value| schema.get("additionalProperties") == Some(&Value::Bool(false));
+
+    let (old_properties, new_properties) = (properties(&old), properties(&new));
+
+    // Changed or removed properties reject the data which used them, as do
+    // new required properties
+    let changed = old_properties
+        .as_object()
+        .into_iter()
+        .flatten()
+        .any(|(name, value)| new_properties.get(name) != Some(value));
+
+    let newly_required = required(&new).iter().any(|r| !required(&old).contains(r));
+
+    if changed || newly_required || old.get("type") != new.get("type") {
+        return Some(Change::Model);
+    }
+
+    // New properties may clash with the extra properties of existing data
+    let added = new_properties
+        .as_object()
+        .into_iter()
+        .flatten()
+        .any(|(name, _)| old_properties.get(name).is_none());
+
+    if (added && !closed(&old)) || (closed(&new) && !closed(&old)) {
+        return Some(Change::Revision);
+    }
+
+    Some(Change::Addition)
+}
@@ -0,0 +1,78 @@
+use std::{
+    fs::{read_to_string, write},
+    path::Path,
+};
+
+use eyre::eyre;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::error::Result;
+
+/// Name of the lock file, next to the plan
+pub(crate) const LOCK_FILE: &str = "ods.lock";
+
+const HEADER: &str =
+    "# Field numbers of the generated schemas, keep this file in version control\n\n";
+
+/// Numbers given to the events and their fields, which must never change once
+/// data is written with them
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Lock {
+    #[serde(default)]
+    pub(crate) protobuf: ProtobufLock,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct ProtobufLock {
+    /// Fields of the envelope message by event name
+    #[serde(default)]
+    pub(crate) envelope: IndexMap<String, u32>,
+    /// Fields of each event message by event name
+    #[serde(default)]
+    pub(crate) messages: IndexMap<String, IndexMap<String, u32>>,
+}
+
+impl Lock {
+    pub(crate) fn read(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            debug!("No lock file at {}", path.display());
+            return Ok(Self::default());
+        }
+
+        let mut lock: Self = toml::from_str(&read_to_string(path)?)
+            .map_err(|e| eyre!("unable to parse {}: {e}", path.display()))?;
+
+        // Tables come back in alphabetical order, keep them by number instead
+        let protobuf = &mut lock.protobuf;
+        protobuf.envelope.sort_by(|_, a, _, b| a.cmp(b));
+        protobuf
+            .messages
+            .sort_by(|a, _, b, _| protobuf.envelope.get(a).cmp(&protobuf.envelope.get(b)));
+
+        for fields in protobuf.messages.values_mut() {
+            fields.sort_by(|_, a, _, b| a.cmp(b));
+        }
+
+        Ok(lock)
+    }
+
+    pub(crate) fn write(&self, path: &Path) -> Result {
+        Ok(write(path, format!("{HEADER}{}", toml::to_string(self)?))?)
+    }
+}
+
+/// Number of a key, giving it the next free one if it has none yet
+pub(crate) fn number(numbers: &mut IndexMap<String, u32>, key: &str) -> u32 {
+    if let Some(number) = numbers.get(key) {
+        return *number;
+    }
+
+    let number = numbers.values().max().map_or(1, |max| max + 1);
+    numbers.insert(key.to_string(), number);
+
+    number
+}
@@ -0,0 +1,319 @@
+use std::{fmt::Write, path::Path};
+
+use clap::{Parser, ValueEnum};
+use eyre::eyre;
+use indexmap::IndexMap;
+use serde::Deserialize;
+use tracing::{instrument, trace};
+
+use crate::{
+    commands::generate::{
+        catalog::{Catalog, Event, EventKind, slug},
+        write,
+    },
+    error::Result,
+};
+
+/// Name of the page for the events without sources
+const UNASSIGNED: &str = "unassigned";
+
+/// Page listing the other pages
+const INDEX: &str = "README.md";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Split {
+    /// One page per plan file
+    #[default]
+    File,
+    /// One page per source, listing the events it sends
+    Source,
+}
+
+#[derive(Debug, Clone, Default, Parser, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MarkdownOpt {
+    /// How to split the markdown documentation into pages [default: file]
+    #[clap(long = "markdown-split", value_enum)]
+    pub split: Option<Split>,
+}
+
+impl MarkdownOpt {
+    pub(crate) fn apply(&mut self, config: &MarkdownOpt) {
+        self.split = self.split.or(config.split);
+    }
+}
+
+/// Page of the documentation, with the events and sources it describes
+#[derive(Debug, Default)]
+struct Page<'a, 'b> {
+    title: String,
+    description: Option<&'a str>,
+    events: Vec<&'b Event<'a>>,
+    sources: Vec<&'a str>,
+}
+
+/// Write the documentation of the plan to the output folder
+#[instrument(name = "markdown", skip_all)]
+pub(crate) fn generate(catalog: &Catalog, options: &MarkdownOpt, output: &Path) -> Result {
+    let split = options.split.unwrap_or_default();
+    let pages = pages(catalog, split)?;
+
+    for (path, page) in &pages {
+        trace!("Writing page: {}", path);
+        write(&output.join(path), &render_page(catalog, split, path, page))?;
+    }
+
+    write(&output.join(INDEX), &render_index(catalog, split, &pages))
+}
+
+/// Path of the page an event or source is documented on, relative to the output
+fn file_page(file: &str) -> String {
+    match file.rsplit_once('.') {
+        Some((stem, _)) => format!("{stem}.md"),
+        None => format!("{file}.md"),
+    }
+}
+
+fn source_page(source: &str) -> String {
+    format!("{}.md", slug(source))
+}
+
+fn pages<'a, 'b>(catalog: &'b Catalog<'a>, split: Split) -> Result<IndexMap<String, Page<'a, 'b>>> {
+    let mut pages = IndexMap::<String, Page>::new();
+
+    match split {
+        Split::File => {
+            for event in &catalog.events {
+                file_page_of(&mut pages, event.file)?.events.push(event);
+            }
+
+            for source in &catalog.sources {
+                file_page_of(&mut pages, source.file)?
+                    .sources
+                    .push(source.name);
+            }
+        }
+        Split::Source => {
+            for name in catalog.source_names() {
+                let path = source_page(name);
+
+                if path.eq_ignore_ascii_case(INDEX) {
+                    return Err(eyre!("`{name}` has the same page `{path}` as the index"));
+                }
+
+                if path == format!("{UNASSIGNED}.md")
+                    && catalog.events.iter().any(|e| e.sources.is_empty())
+                {
+                    return Err(eyre!(
+                        "`{name}` has the same page `{path}` as the events without sources"
+                    ));
+                }
+
+                let page = Page {
+                    title: name.to_string(),
+                    description: catalog.source(name).and_then(|s| s.description),
+                    ..Default::default()
+                };
+
+                if pages.insert(path.clone(), page).is_some() {
+                    return Err(eyre!(
+                        "`{name}` has the same page `{path}` as another source"
+                    ));
+                }
+            }
+
+            for event in &catalog.events {
+                if event.sources.is_empty() {
+                    let page = pages.entry(format!("{UNASSIGNED}.md")).or_default();
+                    page.title = "Unassigned".to_string();
+                    page.description = Some("Events which are not sent by any source");
+                    page.events.push(event);
+                }
+
+                for source in event.sources {
+                    pages[&source_page(source)].events.push(event);
+                }
+            }
+        }
+    }
+
+    Ok(pages)
+}
+
+/// Page of a plan file, which no other plan file nor the index may share
+fn file_page_of<'p, 'a, 'b>(
+    pages: &'p mut IndexMap<String, Page<'a, 'b>>,
+    file: &str,
+) -> Result<&'p mut Page<'a, 'b>> {
+    let path = file_page(file);
+
+    if path.eq_ignore_ascii_case(INDEX) {
+        return Err(eyre!("`{file}` has the same page `{path}` as the index"));
+    }
+
+    let page = pages.entry(path.clone()).or_default();
+
+    if !page.title.is_empty() && page.title != file {
+        return Err(eyre!(
+            "`{file}` has the same page `{path}` as another plan file"
+        ));
+    }
+
+    page.title = file.to_string();
+
+    Ok(page)
+}
+
+/// Link from a page to a file relative to the output folder
+fn link(from: &str, to: &str) -> String {
+    let depth = from.matches('/').count();
+    format!("{}{to}", "../".repeat(depth))
+}
+
+/// Page documenting a source, and the anchor to it if it is declared
+fn source_link(catalog: &Catalog, split: Split, name: &str) -> Option<String> {
+    match split {
+        Split::File => catalog
+            .source(name)
+            .map(|s| format!("{}#{}", file_page(s.file), s.anchor)),
+        Split::Source => Some(source_page(name)),
+    }
+}
+
+/// Page an event is documented on, picking the first one when there are several
+fn event_link(split: Split, event: &Event) -> String {
+    let page = match (split, event.sources.first()) {
+        (Split::File, _) => file_page(event.file),
+        (Split::Source, Some(source)) => source_page(source),
+        (Split::Source, None) => format!("{UNASSIGNED}.md"),
+    };
+
+    format!("{page}#{}", event.anchor)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+fn render_index(catalog: &Catalog, split: Split, pages: &IndexMap<String, Page>) -> String {
+    let mut out = String::from("# Data plan\n\n## Contents\n\n");
+
+    for (path, page) in pages {
+        let _ = writeln!(out, "- [{}]({path})", page.title);
+
+        for event in &page.events {
+            let _ = writeln!(out, "  - [{}]({path}#{})", event.name, event.anchor);
+        }
+    }
+
+    let sources = catalog.source_names();
+
+    out.push_str("\n## Source matrix\n\n| Event | Type |");
+    for source in &sources {
+        match source_link(catalog, split, source) {
+            Some(link) => {
+                let _ = write!(out, " [{}]({link}) |", escape(source));
+            }
+            None => {
+                let _ = write!(out, " {} |", escape(source));
+            }
+        }
+    }
+
+    out.push_str("\n| --- | --- |");
+    out.push_str(&" :-: |".repeat(sources.len()));
+    out.push('\n');
+
+    for event in &catalog.events {
+        let _ = write!(
+            out,
+            "| [{}]({}) | {} |",
+            escape(event.name),
+            event_link(split, event),
+            event.kind.name()
+        );
+
+        for source in &sources {
+            if event.sources.iter().any(|s| s == source) {
+                out.push_str(" ✓ |");
+            } else {
+                out.push_str("   |");
+            }
+        }
+
+        out.push('\n');
+    }
+
+    out
+}
+
+fn render_page(catalog: &Catalog, split: Split, path: &str, page: &Page) -> String {
+    let mut out = format!("# {}\n\n", page.title);
+
+    if let Some(description) = page.description {
+        let _ = writeln!(out, "{}\n", description.trim());
+    }
+
+    let _ = writeln!(out, "[Back to contents]({})", link(path, INDEX));
+
+    for (kind, heading) in [
+        (EventKind::Metric, "Metrics"),
+        (EventKind::Pageview, "Pageviews"),
+    ] {
+        let events = page.events.iter().filter(|e| e.kind == kind);
+
+        for (i, event) in events.enumerate() {
+            if i == 0 {
+                let _ = writeln!(out, "\n## {heading}");
+            }
+
+            let _ = writeln!(
+                out,
+                "\n<a id=\"{}\"></a>\n\n### {}\n",
+                event.anchor, event.name
+            );
+
+            if let Some(description) = event.description {
+                let _ = writeln!(out, "{}\n", description.trim());
+            }
+
+            let sources = event
+                .sources
+                .iter()
+                .map(|name| match source_link(catalog, split, name) {
+                    Some(to) => format!("[{name}]({})", link(path, &to)),
+                    None => name.to_string(),
+                })
+                .collect::<Vec<_>>();
+
+            if sources.is_empty() {
+                out.push_str("**Sources:** none\n");
+            } else {
+                let _ = writeln!(out, "**Sources:** {}", sources.join(", "));
+            }
+        }
+    }
+
+    for (i, name) in page.sources.iter().enumerate() {
+        if i == 0 {
+            out.push_str("\n## Sources\n");
+        }
+
+        let Some(source) = catalog.source(name) else {
+            continue;
+        };
+
+        let _ = writeln!(
+            out,
+            "\n<a id=\"{}\"></a>\n\n### {}",
+            source.anchor, source.name
+        );
+
+        if let Some(description) = source.description {
+            let _ = writeln!(out, "\n{}", description.trim());
+        }
+    }
+
+    out
+}
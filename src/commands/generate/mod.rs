@@ -1,10 +1,74 @@
-use std::{fs::create_dir_all, path::PathBuf};
+use std::{
+    fs::{create_dir_all, write as write_file},
+    path::{Component, Path, PathBuf},
+};
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use eyre::eyre;
-use tracing::instrument;
+use serde::Deserialize;
+use tracing::{debug, instrument, trace};
 
-use crate::{error::Result, schema::SchemaOpt};
+use crate::{
+    config::Config,
+    error::Result,
+    schema::{DEFAULT_OUTPUT, SchemaOpt},
+};
+
+use self::{
+    catalog::Catalog,
+    lock::{LOCK_FILE, Lock},
+};
+
+mod amplitude;
+mod avro;
+pub(crate) mod catalog;
+pub mod dbt;
+mod go;
+pub(crate) mod html;
+pub mod iglu;
+mod lock;
+pub mod markdown;
+mod plugin;
+mod protobuf;
+mod python;
+mod segment;
+pub mod sql;
+mod template;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GenerateFormat {
+    /// Documentation of the events, one page per file or per source
+    Markdown,
+    /// Static site to search and browse the plan
+    Html,
+    /// Tracking plan rules for the Segment Public API
+    SegmentProtocols,
+    /// Taxonomy CSV for the Amplitude Data import
+    AmplitudeCsv,
+    /// Self-describing JSON schemas in the layout of an Iglu repository
+    Iglu,
+    /// Warehouse tables for the events
+    Sql,
+    /// dbt source declaring the event tables, with staging models
+    Dbt,
+    /// Avro record schemas for the events
+    Avro,
+    /// Protobuf messages for the events, with an envelope holding any of them
+    Protobuf,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GenerateLang {
+    /// Dataclasses with type hints, along with a `Tracker` protocol
+    Python,
+    /// Structs with JSON tags, along with a `Tracker` interface
+    Go,
+}
+
+/// Package of the Protobuf messages and namespace of the Avro records
+const DEFAULT_PACKAGE: &str = "ods.events";
 
 /// Generate code from plan
 #[derive(Debug, Parser)]
@@ -14,29 +78,220 @@ pub struct Generate {
 
     #[clap(short)]
     pub output: Option<PathBuf>,
+
+    /// Formats to generate, separated by commas
+    #[clap(short, long, value_enum, value_delimiter = ',')]
+    pub format: Vec<GenerateFormat>,
+
+    /// Languages to generate typed events for, separated by commas
+    #[clap(long, value_enum, value_delimiter = ',')]
+    pub lang: Vec<GenerateLang>,
+
+    /// Folder of MiniJinja templates to render into the output
+    #[clap(long)]
+    pub template: Option<PathBuf>,
+
+    /// Executables to generate with, sent the plan as JSON on stdin and
+    /// replying with the files to write as JSON on stdout
+    #[clap(long)]
+    pub plugin: Vec<String>,
+
+    /// Parameter sent to the plugins, as `KEY=VALUE`
+    #[clap(long, value_name = "KEY=VALUE", value_parser = plugin::parameter)]
+    pub plugin_opt: Vec<(String, String)>,
+
+    /// Package of the generated code, which is the Protobuf package, the Avro
+    /// namespace and the Go package after the last dot [default: ods.events]
+    #[clap(long)]
+    pub package: Option<String>,
+
+    #[clap(flatten)]
+    pub markdown: markdown::MarkdownOpt,
+
+    #[clap(flatten)]
+    pub iglu: iglu::IgluOpt,
+
+    #[clap(flatten)]
+    pub sql: sql::SqlOpt,
+
+    #[clap(flatten)]
+    pub dbt: dbt::DbtOpt,
+
+    /// Generate the whole output again whenever a plan file changes
+    #[clap(long)]
+    pub watch: bool,
 }
 
 impl Generate {
+    pub(crate) fn apply(&mut self, config: &Config) {
+        self.schema.apply(config);
+
+        if self.output.is_none() {
+            self.output = config.generate.output.as_ref().map(|o| config.resolve(o));
+        }
+
+        if self.format.is_empty() {
+            self.format = config.generate.formats.clone();
+        }
+
+        if self.lang.is_empty() {
+            self.lang = config.generate.langs.clone();
+        }
+
+        if self.template.is_none() {
+            self.template = config.generate.template.as_ref().map(|t| config.resolve(t));
+        }
+
+        if self.plugin.is_empty() {
+            // Plugins given as paths are relative to the config
+            self.plugin = config
+                .generate
+                .plugins
+                .iter()
+                .map(|p| match p.contains('/') {
+                    true => config.resolve(Path::new(p)).to_string_lossy().into(),
+                    false => p.clone(),
+                })
+                .collect();
+        }
+
+        // Parameters on the command line come last to override the config
+        let parameters = config.generate.plugin_opts.clone().into_iter();
+        self.plugin_opt = parameters.chain(self.plugin_opt.drain(..)).collect();
+
+        if self.package.is_none() {
+            self.package = config.generate.package.clone();
+        }
+
+        self.markdown.apply(&config.generate.markdown);
+
+        // Only the path from the config is relative to it
+        let mut iglu = config.generate.iglu.clone();
+        iglu.previous = iglu.previous.map(|p| config.resolve(&p));
+        self.iglu.apply(&iglu);
+
+        self.sql.apply(&config.generate.sql);
+        self.dbt.apply(&config.generate.dbt);
+    }
+
     #[instrument(name = "gen", skip_all)]
     pub(crate) fn run(&self) -> Result {
-        let files = self.schema.load()?;
+        let plan = self.schema.plan()?;
+
+        // If the plan is a file, write next to it in the parent folder
+        let dir = match plan.is_file() {
+            true => plan
+                .parent()
+                .ok_or(eyre!("unable to figure out where to write the output"))?,
+            false => plan,
+        };
 
         let output = match &self.output {
             Some(output) => output,
-            // If the plan is a file, write the output to the parent folder
-            None if self.schema.plan.is_file() => &self
-                .schema
-                .plan
-                .parent()
-                .ok_or(eyre!("unable to figure out where to write the output"))?
-                .join("ods"),
-            None => &self.schema.plan.join("ods"),
+            None => &dir.join(DEFAULT_OUTPUT),
         };
+        let lock = dir.join(LOCK_FILE);
+
+        // Generated files in the plan folder must not be loaded as plan files
+        let schema = SchemaOpt {
+            exclude_dir: Some(output.clone()),
+            ..self.schema.clone()
+        };
+
+        if self.watch {
+            return schema.watch(Some(output), || self.generate(&schema, output, &lock));
+        }
+
+        self.generate(&schema, output, &lock)
+    }
+
+    fn generate(&self, schema: &SchemaOpt, output: &Path, lock_path: &Path) -> Result {
+        let files = schema.load()?;
+
+        schema.check_strict(&files)?;
 
         create_dir_all(output)?;
 
-        for (_name, _spec) in files {}
+        if self.format.is_empty()
+            && self.lang.is_empty()
+            && self.template.is_none()
+            && self.plugin.is_empty()
+        {
+            debug!("No format, language, template or plugin given, nothing to generate");
+        }
+
+        let catalog = Catalog::new(&files);
+        let package = self.package.as_deref().unwrap_or(DEFAULT_PACKAGE);
+
+        for format in &self.format {
+            match format {
+                GenerateFormat::Markdown => markdown::generate(&catalog, &self.markdown, output)?,
+                GenerateFormat::Html => html::generate(&catalog, output)?,
+                GenerateFormat::SegmentProtocols => segment::generate(&catalog, output)?,
+                GenerateFormat::AmplitudeCsv => amplitude::generate(&catalog, output)?,
+                GenerateFormat::Iglu => iglu::generate(&catalog, &self.iglu, output)?,
+                GenerateFormat::Sql => sql::generate(&catalog, &self.sql, output)?,
+                GenerateFormat::Dbt => dbt::generate(&catalog, &self.dbt, &self.sql, output)?,
+                GenerateFormat::Avro => avro::generate(&catalog, package, output)?,
+                GenerateFormat::Protobuf => {
+                    let mut lock = Lock::read(lock_path)?;
+                    let previous = lock.clone();
+
+                    protobuf::generate(&catalog, package, &mut lock.protobuf, output)?;
+
+                    if lock != previous {
+                        debug!("Updating {}", lock_path.display());
+                        lock.write(lock_path)?;
+                    }
+                }
+            }
+        }
+
+        for lang in &self.lang {
+            match lang {
+                GenerateLang::Python => python::generate(&catalog, output)?,
+                GenerateLang::Go => go::generate(&catalog, package, output)?,
+            }
+        }
+
+        if let Some(template) = &self.template {
+            template::generate(&catalog, package, template, output)?;
+        }
+
+        let parameters = self.plugin_opt.iter().cloned().collect();
+        for plugin in &self.plugin {
+            plugin::generate(&catalog, plugin, package, &parameters, output)?;
+        }
 
         Ok(())
     }
 }
+
+/// Category of the items of a plan file for the tools grouping them, which
+/// the importers turn back into the file name
+pub(crate) fn category(file: &str) -> &str {
+    Path::new(file)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or(file)
+}
+
+/// Whether a relative path given by a plugin or rendered from a template stays
+/// inside the output
+pub(crate) fn is_inside(path: &str) -> bool {
+    !path.is_empty()
+        && Path::new(path)
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+}
+
+/// Write a generated file, creating its folder if needed
+pub(crate) fn write(path: &Path, content: &str) -> Result {
+    trace!("Writing file: {}", path.display());
+
+    if let Some(parent) = path.parent() {
+        create_dir_all(parent)?;
+    }
+
+    Ok(write_file(path, content)?)
+}
@@ -0,0 +1,117 @@
+use std::{
+    io::Write as IoWrite,
+    path::Path,
+    process::{Command, Stdio},
+    thread::spawn,
+};
+
+use eyre::eyre;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument, trace};
+
+use crate::{
+    commands::generate::{catalog::Catalog, is_inside, write},
+    error::Result,
+};
+
+/// What the plugins are sent on stdin
+#[derive(Debug, Serialize)]
+struct Request<'a> {
+    plan: &'a Catalog<'a>,
+    options: Options<'a>,
+}
+
+#[derive(Debug, Serialize)]
+struct Options<'a> {
+    /// Package of the generated code
+    package: &'a str,
+    /// Parameters given with `--plugin-opt`
+    parameters: &'a IndexMap<String, String>,
+}
+
+/// File the plugins send back on stdout
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct File {
+    path: String,
+    content: String,
+}
+
+/// Parse a `KEY=VALUE` plugin parameter
+pub(crate) fn parameter(value: &str) -> std::result::Result<(String, String), String> {
+    value
+        .split_once('=')
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .ok_or(format!("`{value}` is not a `KEY=VALUE` pair"))
+}
+
+/// Run a plugin executable with the plan on stdin, and write the files it
+/// sends back on stdout to the output
+#[instrument(name = "plugin", skip_all, fields(plugin = plugin))]
+pub(crate) fn generate(
+    catalog: &Catalog,
+    plugin: &str,
+    package: &str,
+    parameters: &IndexMap<String, String>,
+    output: &Path,
+) -> Result {
+    let name = Path::new(plugin)
+        .file_name()
+        .map_or(plugin.into(), |name| name.to_string_lossy());
+
+    let request = serde_json::to_vec(&Request {
+        plan: catalog,
+        options: Options {
+            package,
+            parameters,
+        },
+    })?;
+
+    debug!("Running {plugin}");
+
+    let mut child = Command::new(plugin)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .map_err(|e| eyre!("unable to run plugin {name}: {e}"))?;
+
+    // The plan is written from another thread while stdout is read, as
+    // plugins may reply before reading all of it. They may also exit without
+    // reading it, which the exit status reports better than the broken pipe.
+    let stdin = child.stdin.take();
+    let writer = spawn(move || {
+        if let Some(mut stdin) = stdin {
+            let _ = stdin.write_all(&request);
+        }
+    });
+
+    let result = child.wait_with_output()?;
+    let _ = writer.join();
+
+    if !result.status.success() {
+        return Err(eyre!("plugin {name} failed with {}", result.status));
+    }
+
+    let files: Vec<File> = serde_json::from_slice(&result.stdout)
+        .map_err(|e| eyre!("unable to parse the output of plugin {name}: {e}"))?;
+
+    // Plugins only get to write inside the output, which is checked for every
+    // file before writing any
+    for file in &files {
+        if !is_inside(&file.path) {
+            return Err(eyre!(
+                "plugin {name} tried to write {} outside of the output",
+                file.path
+            ));
+        }
+    }
+
+    for file in files {
+        trace!("Plugin {name} wrote {}", file.path);
+        write(&output.join(&file.path), &file.content)?;
+    }
+
+    Ok(())
+}
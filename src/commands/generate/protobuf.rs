@@ -0,0 +1,98 @@
+use std::{fmt::Write, path::Path};
+
+use tracing::instrument;
+
+use crate::{
+    commands::{
+        generate::{
+            catalog::Catalog,
+            lock::{ProtobufLock, number},
+            sql::COLUMNS,
+            write,
+        },
+        lint::rules::uses_name_case::Case,
+    },
+    error::Result,
+};
+
+/// Write a message per metric and pageview to `events.proto`, along with an
+/// `Event` envelope holding any of them
+#[instrument(name = "protobuf", skip_all)]
+pub(crate) fn generate(
+    catalog: &Catalog,
+    package: &str,
+    lock: &mut ProtobufLock,
+    output: &Path,
+) -> Result {
+    let mut out =
+        String::from("// Generated by ods from the data plan\n\nsyntax = \"proto3\";\n\n");
+    let _ = writeln!(out, "package {package};\n");
+    out.push_str("import \"google/protobuf/timestamp.proto\";\n");
+
+    let mut members = vec![];
+
+    for (event, message) in catalog
+        .events
+        .iter()
+        .zip(catalog.type_names("Protobuf message")?)
+    {
+        out.push('\n');
+
+        if let Some(description) = event.description {
+            comment(&mut out, "", description);
+        }
+
+        let _ = writeln!(out, "message {message} {{");
+
+        let fields = lock.messages.entry(event.name.to_string()).or_default();
+        for (column, description, _) in COLUMNS {
+            let kind = match column {
+                "timestamp" => "google.protobuf.Timestamp",
+                "user_id" => "optional string",
+                _ => "string",
+            };
+
+            comment(&mut out, "  ", description);
+            let _ = writeln!(out, "  {kind} {column} = {};", number(fields, column));
+        }
+
+        out.push_str("}\n");
+
+        let number = number(&mut lock.envelope, event.name);
+        members.push((message, Case::SnakeCase.convert(event.name), number));
+    }
+
+    out.push_str("\n// Any event of the plan\nmessage Event {\n");
+
+    // Numbers of removed events are never given out again
+    let removed = lock
+        .envelope
+        .iter()
+        .filter(|(name, _)| !catalog.events.iter().any(|e| e.name == *name))
+        .map(|(name, number)| (Case::SnakeCase.convert(name), *number))
+        .collect::<Vec<_>>();
+
+    for (field, number) in &removed {
+        let _ = writeln!(out, "  reserved {number};\n  reserved \"{field}\";");
+    }
+
+    if !removed.is_empty() {
+        out.push('\n');
+    }
+
+    out.push_str("  oneof event {\n");
+
+    for (message, field, number) in members {
+        let _ = writeln!(out, "    {message} {field} = {number};");
+    }
+
+    out.push_str("  }\n}\n");
+
+    write(&output.join("protobuf").join("events.proto"), &out)
+}
+
+fn comment(out: &mut String, indent: &str, text: &str) {
+    for line in text.trim().lines() {
+        let _ = writeln!(out, "{indent}// {line}");
+    }
+}
@@ -0,0 +1,122 @@
+use std::{fmt::Write, path::Path};
+
+use tracing::instrument;
+
+use crate::{
+    commands::generate::{
+        catalog::{Catalog, Event},
+        sql::COLUMNS,
+        write,
+    },
+    error::Result,
+};
+
+const HEADER: &str = r#""""Events of the data plan, generated by ods"""
+
+from dataclasses import asdict, dataclass, field
+from datetime import datetime, timezone
+from typing import ClassVar, Literal, NoReturn, Protocol, TypeAlias
+from uuid import uuid4
+
+
+@dataclass(frozen=True, kw_only=True)
+class BaseEvent:
+    """Fields every event has"""
+
+    name: ClassVar[str]
+    kind: ClassVar[str]
+
+"#;
+
+const TO_DICT: &str = r#"
+    def to_dict(self) -> dict[str, object]:
+        """Event as JSON-compatible values, keyed by field"""
+        return {
+            "event": self.name,
+            **asdict(self),
+            "timestamp": self.timestamp.isoformat(),
+        }
+"#;
+
+/// Write a dataclass per metric and pageview to `events.py`, along with a
+/// `Tracker` protocol sending any of them
+#[instrument(name = "python", skip_all)]
+pub(crate) fn generate(catalog: &Catalog, output: &Path) -> Result {
+    let names = catalog.type_names("Python class")?;
+    let mut out = String::from(HEADER);
+
+    for (column, description, _) in COLUMNS {
+        let field = match column {
+            "event_id" => "str = field(default_factory=lambda: str(uuid4()))",
+            "timestamp" => "datetime = field(default_factory=lambda: datetime.now(timezone.utc))",
+            "user_id" => "str | None = None",
+            // Each event narrows the sources down
+            _ => "str",
+        };
+
+        let _ = writeln!(out, "    {column}: {field}");
+        let _ = writeln!(out, "    \"\"\"{description}\"\"\"\n");
+    }
+
+    out.truncate(out.trim_end().len());
+    out.push('\n');
+    out.push_str(TO_DICT);
+
+    for (event, name) in catalog.events.iter().zip(&names) {
+        out.push_str("\n\n");
+        class(&mut out, name, event);
+    }
+
+    let union = match names.is_empty() {
+        true => "NoReturn".to_string(),
+        false => names.join(" | "),
+    };
+
+    let _ = write!(
+        out,
+        r#"
+
+Event: TypeAlias = {union}
+"""Any event of the plan"""
+
+
+class Tracker(Protocol):
+    """Sends events, implemented by the analytics client"""
+
+    def track(self, event: Event) -> None: ...
+"#
+    );
+
+    write(&output.join("python").join("events.py"), &out)
+}
+
+fn class(out: &mut String, name: &str, event: &Event) {
+    let _ = writeln!(out, "@dataclass(frozen=True, kw_only=True)");
+    let _ = writeln!(out, "class {name}(BaseEvent):");
+
+    if let Some(description) = event.description {
+        let _ = writeln!(out, "    \"\"\"{}\"\"\"\n", docstring(description));
+    }
+
+    let _ = writeln!(out, "    name: ClassVar[str] = {}", string(event.name));
+    let _ = writeln!(out, "    kind: ClassVar[str] = \"{}\"", event.kind.name());
+
+    if !event.sources.is_empty() {
+        let sources = event.sources.iter().map(|s| string(s)).collect::<Vec<_>>();
+
+        let _ = writeln!(out, "    source: Literal[{}]", sources.join(", "));
+    }
+}
+
+/// Body of a docstring, escaping every quote so that none can end it early
+fn docstring(text: &str) -> String {
+    text.trim()
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\n    ")
+}
+
+/// Python string literal, which JSON strings are valid as
+fn string(value: &str) -> String {
+    serde_json::Value::from(value).to_string()
+}
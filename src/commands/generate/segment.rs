@@ -0,0 +1,75 @@
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::to_string_pretty;
+use tracing::instrument;
+
+use crate::{
+    commands::generate::{
+        catalog::{Catalog, EventKind},
+        category, write,
+    },
+    error::Result,
+};
+
+/// Rules in the shape the Segment Public API takes to update a tracking plan
+#[derive(Debug, Serialize)]
+struct Plan<'a> {
+    rules: Vec<Rule<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct Rule<'a> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    key: &'a str,
+    labels: Labels<'a>,
+    #[serde(rename = "jsonSchema")]
+    json_schema: JsonSchema<'a>,
+    version: u32,
+}
+
+#[derive(Debug, Serialize)]
+struct Labels<'a> {
+    category: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonSchema<'a> {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<&'a str>,
+    #[serde(rename = "type")]
+    kind: &'static str,
+}
+
+/// Write the metrics and pageviews as a Segment Protocols tracking plan
+#[instrument(name = "segment_protocols", skip_all)]
+pub(crate) fn generate(catalog: &Catalog, output: &Path) -> Result {
+    let rules = catalog
+        .events
+        .iter()
+        .map(|event| Rule {
+            kind: match event.kind {
+                EventKind::Metric => "TRACK",
+                EventKind::Pageview => "PAGE",
+            },
+            key: event.name,
+            labels: Labels {
+                category: category(event.file),
+            },
+            json_schema: JsonSchema {
+                schema: "http://json-schema.org/draft-07/schema#",
+                description: event.description.map(str::trim),
+                kind: "object",
+            },
+            version: 1,
+        })
+        .collect();
+
+    write(
+        &output.join("segment-protocols.json"),
+        &format!("{}\n", to_string_pretty(&Plan { rules })?),
+    )
+}
@@ -0,0 +1,246 @@
+use std::{collections::HashSet, fmt::Write, path::Path};
+
+use clap::{Parser, ValueEnum};
+use eyre::eyre;
+use serde::Deserialize;
+use tracing::instrument;
+
+use crate::{
+    commands::{
+        generate::{
+            catalog::{Catalog, Event},
+            write,
+        },
+        lint::rules::uses_name_case::Case,
+    },
+    error::Result,
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Dialect {
+    #[default]
+    Postgres,
+    Bigquery,
+    Snowflake,
+    Clickhouse,
+}
+
+#[derive(Debug, Clone, Default, Parser, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SqlOpt {
+    /// SQL dialect of the event tables [default: postgres]
+    #[clap(long = "sql-dialect", value_enum)]
+    pub dialect: Option<Dialect>,
+
+    /// Case of the event table names [default: snake_case]
+    #[clap(long = "sql-case", value_enum)]
+    pub case: Option<Case>,
+}
+
+impl SqlOpt {
+    pub(crate) fn apply(&mut self, config: &SqlOpt) {
+        self.dialect = self.dialect.or(config.dialect);
+        self.case = self.case.take().or(config.case.clone());
+    }
+
+    /// Name of the table of an event
+    pub(crate) fn table(&self, event: &Event) -> String {
+        self.case
+            .as_ref()
+            .unwrap_or(&Case::SnakeCase)
+            .convert(event.name)
+    }
+
+    /// Name of the table of each event, which must be unique across the plan
+    pub(crate) fn tables(&self, catalog: &Catalog) -> Result<Vec<String>> {
+        let mut seen = HashSet::new();
+
+        catalog
+            .events
+            .iter()
+            .map(|event| {
+                let table = self.table(event);
+
+                if !seen.insert(table.clone()) {
+                    return Err(eyre!(
+                        "`{}` has the same table name `{table}` as another event",
+                        event.name
+                    ));
+                }
+
+                Ok(table)
+            })
+            .collect()
+    }
+}
+
+/// Columns every event table has, with their description and their type in
+/// each dialect in the order of [`Dialect`]
+pub(crate) const COLUMNS: [(&str, &str, [&str; 4]); 4] = [
+    (
+        "event_id",
+        "Unique identifier of the event",
+        [
+            "UUID NOT NULL",
+            "STRING NOT NULL",
+            "VARCHAR NOT NULL",
+            "UUID",
+        ],
+    ),
+    (
+        "timestamp",
+        "Time the event happened at",
+        [
+            "TIMESTAMPTZ NOT NULL",
+            "TIMESTAMP NOT NULL",
+            "TIMESTAMP_TZ NOT NULL",
+            "DateTime64(3, 'UTC')",
+        ],
+    ),
+    (
+        "source",
+        "Source which sent the event",
+        [
+            "TEXT NOT NULL",
+            "STRING NOT NULL",
+            "VARCHAR NOT NULL",
+            "LowCardinality(String)",
+        ],
+    ),
+    (
+        "user_id",
+        "Identifier of the user who triggered the event, if known",
+        ["TEXT", "STRING", "VARCHAR", "Nullable(String)"],
+    ),
+];
+
+impl Dialect {
+    fn quote(self, identifier: &str) -> String {
+        match self {
+            Dialect::Postgres | Dialect::Snowflake => {
+                format!("\"{}\"", identifier.replace('"', "\"\""))
+            }
+            Dialect::Bigquery | Dialect::Clickhouse => {
+                format!("`{}`", identifier.replace('`', "\\`"))
+            }
+        }
+    }
+
+    fn string(self, text: &str) -> String {
+        match self {
+            Dialect::Postgres | Dialect::Snowflake => format!("'{}'", text.replace('\'', "''")),
+            Dialect::Bigquery | Dialect::Clickhouse => format!(
+                "'{}'",
+                text.replace('\\', "\\\\")
+                    .replace('\'', "\\'")
+                    .replace('\n', "\\n")
+            ),
+        }
+    }
+}
+
+/// Write a `CREATE TABLE` statement per metric and pageview to `events.sql`.
+/// Every table has the [`COLUMNS`].
+#[instrument(name = "sql", skip_all)]
+pub(crate) fn generate(catalog: &Catalog, options: &SqlOpt, output: &Path) -> Result {
+    let dialect = options.dialect.unwrap_or_default();
+
+    let statements = catalog
+        .events
+        .iter()
+        .zip(options.tables(catalog)?)
+        .map(|(event, name)| table(dialect, &name, event))
+        .collect::<Vec<_>>();
+
+    write(&output.join("events.sql"), &statements.join("\n"))
+}
+
+fn table(dialect: Dialect, name: &str, event: &Event) -> String {
+    let table = dialect.quote(name);
+    let description = event.description.map(str::trim);
+
+    let mut out = format!("-- {} {}\n", event.kind.name(), event.name);
+    let _ = writeln!(out, "CREATE TABLE IF NOT EXISTS {table} (");
+
+    let mut lines = COLUMNS
+        .iter()
+        .map(|(column, _, types)| {
+            format!("  {} {}", dialect.quote(column), types[dialect as usize])
+        })
+        .collect::<Vec<_>>();
+
+    match dialect {
+        Dialect::Postgres | Dialect::Snowflake => {
+            lines.push(format!("  PRIMARY KEY ({})", dialect.quote("event_id")));
+        }
+        Dialect::Bigquery | Dialect::Clickhouse => {}
+    }
+
+    // Only Postgres enforces check constraints
+    if dialect == Dialect::Postgres && !event.sources.is_empty() {
+        let sources = event
+            .sources
+            .iter()
+            .map(|s| dialect.string(s))
+            .collect::<Vec<_>>();
+
+        lines.push(format!(
+            "  CHECK ({} IN ({}))",
+            dialect.quote("source"),
+            sources.join(", ")
+        ));
+    }
+
+    let _ = write!(out, "{}\n)", lines.join(",\n"));
+
+    match dialect {
+        Dialect::Postgres => {
+            out.push_str(";\n");
+
+            if let Some(description) = description {
+                let _ = writeln!(
+                    out,
+                    "COMMENT ON TABLE {table} IS {};",
+                    dialect.string(description)
+                );
+            }
+        }
+        Dialect::Bigquery => {
+            let _ = write!(out, "\nPARTITION BY DATE({})", dialect.quote("timestamp"));
+
+            if let Some(description) = description {
+                let _ = write!(
+                    out,
+                    "\nOPTIONS (description = {})",
+                    dialect.string(description)
+                );
+            }
+
+            out.push_str(";\n");
+        }
+        Dialect::Snowflake => {
+            if let Some(description) = description {
+                let _ = write!(out, "\nCOMMENT = {}", dialect.string(description));
+            }
+
+            out.push_str(";\n");
+        }
+        Dialect::Clickhouse => {
+            let _ = write!(
+                out,
+                "\nENGINE = MergeTree\nORDER BY ({}, {})",
+                dialect.quote("timestamp"),
+                dialect.quote("event_id")
+            );
+
+            if let Some(description) = description {
+                let _ = write!(out, "\nCOMMENT {}", dialect.string(description));
+            }
+
+            out.push_str(";\n");
+        }
+    }
+
+    out
+}
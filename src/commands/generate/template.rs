@@ -0,0 +1,175 @@
+use std::{
+    collections::HashSet,
+    fs::{read_dir, read_to_string},
+    path::{Path, PathBuf},
+};
+
+use clap::ValueEnum;
+use eyre::eyre;
+use minijinja::{Environment, UndefinedBehavior};
+use serde::Serialize;
+use tracing::{debug, instrument, trace};
+
+use crate::{
+    commands::{
+        generate::{
+            catalog::{Catalog, Event, EventKind, Source},
+            is_inside, write,
+        },
+        lint::rules::uses_name_case::Case,
+    },
+    error::Result,
+};
+
+/// Extension stripped from the template names to get the output paths
+const EXTENSION: &str = ".jinja";
+
+/// What the templates are rendered with
+#[derive(Debug, Serialize)]
+struct Context<'a> {
+    /// Package of the generated code
+    package: &'a str,
+    /// Metrics and pageviews in the order of the plan
+    events: &'a [Event<'a>],
+    metrics: Vec<&'a Event<'a>>,
+    pageviews: Vec<&'a Event<'a>>,
+    sources: &'a [Source<'a>],
+    /// Event of the file, for the templates rendered once per event
+    #[serde(skip_serializing_if = "Option::is_none")]
+    event: Option<&'a Event<'a>>,
+}
+
+/// Render every template of a folder to the same path in the output.
+///
+/// Templates under a name starting with `_` are only there to be included or
+/// imported by others. Templates with an expression in their path are
+/// rendered once per event, with the event in the context.
+#[instrument(name = "template", skip_all)]
+pub(crate) fn generate(catalog: &Catalog, package: &str, dir: &Path, output: &Path) -> Result {
+    if !dir.is_dir() {
+        return Err(eyre!("template folder {} not found", dir.display()));
+    }
+
+    let mut env = Environment::new();
+    env.set_undefined_behavior(UndefinedBehavior::Strict);
+    env.set_keep_trailing_newline(true);
+    env.set_trim_blocks(true);
+    env.set_lstrip_blocks(true);
+
+    for case in Case::value_variants() {
+        let Some(name) = case.to_possible_value() else {
+            continue;
+        };
+
+        let case = case.clone();
+        env.add_filter(name.get_name().to_string(), move |value: &str| {
+            case.convert(value)
+        });
+    }
+
+    let mut names = vec![];
+    for path in files(dir)? {
+        let name = path
+            .strip_prefix(dir)?
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        env.add_template_owned(name.clone(), read_to_string(&path)?)
+            .map_err(|e| eyre!("unable to parse template {name}: {e}"))?;
+
+        if !name.split('/').any(|part| part.starts_with('_')) {
+            names.push(name);
+        }
+    }
+
+    debug!("Rendering {} templates", names.len());
+
+    let mut context = Context {
+        package,
+        events: &catalog.events,
+        metrics: by_kind(catalog, EventKind::Metric),
+        pageviews: by_kind(catalog, EventKind::Pageview),
+        sources: &catalog.sources,
+        event: None,
+    };
+
+    // Paths rendered so far, as two events may give the same path
+    let mut paths = HashSet::new();
+
+    for name in names {
+        let path = name.strip_suffix(EXTENSION).unwrap_or(&name);
+
+        match path.contains("{{") {
+            true => {
+                for event in &catalog.events {
+                    context.event = Some(event);
+                    render(&env, &name, path, &context, &mut paths, output)?;
+                }
+
+                context.event = None;
+            }
+            false => render(&env, &name, path, &context, &mut paths, output)?,
+        }
+    }
+
+    Ok(())
+}
+
+fn render(
+    env: &Environment,
+    name: &str,
+    path: &str,
+    context: &Context,
+    paths: &mut HashSet<String>,
+    output: &Path,
+) -> Result {
+    let path = env
+        .render_str(path, context)
+        .map_err(|e| eyre!("unable to render the path of {name}: {e}"))?;
+
+    // Names in the plan only get to write inside the output
+    if !is_inside(&path) {
+        return Err(eyre!(
+            "template {name} tried to write {path} outside of the output"
+        ));
+    }
+
+    if !paths.insert(path.clone()) {
+        return Err(eyre!("template {name} renders to {path} more than once"));
+    }
+
+    let content = env
+        .get_template(name)?
+        .render(context)
+        .map_err(|e| eyre!("unable to render {name}: {e}"))?;
+
+    trace!("Rendered {name} to {path}");
+    write(&output.join(path), &content)
+}
+
+fn by_kind<'a>(catalog: &'a Catalog<'a>, kind: EventKind) -> Vec<&'a Event<'a>> {
+    catalog.events.iter().filter(|e| e.kind == kind).collect()
+}
+
+/// Files of a folder and its subfolders, sorted by path. Symlinks to folders
+/// are skipped so that a loop can't recurse forever.
+fn files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = vec![];
+
+    for entry in read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+
+        if entry.file_type()?.is_dir() {
+            files.extend(self::files(&path)?);
+        } else if path.is_file() {
+            files.push(path);
+        }
+    }
+
+    files.sort();
+
+    Ok(files)
+}
@@ -0,0 +1,192 @@
+use std::{
+    fs::{create_dir_all, write},
+    path::PathBuf,
+};
+
+use anstream::println;
+use clap::Parser;
+use eyre::eyre;
+use indexmap::IndexMap;
+use owo_colors::OwoColorize;
+use serde_yml::{Mapping, Value};
+
+use crate::{
+    commands::fmt::yaml::{self, Comments},
+    error::Result,
+};
+
+mod segment;
+mod taxonomy;
+
+/// Convert a tracking plan from another tool
+#[derive(Debug, Parser)]
+pub enum Import {
+    /// Import an Amplitude Data taxonomy CSV export
+    Amplitude(taxonomy::Taxonomy),
+    /// Import a Mixpanel Lexicon CSV export
+    Mixpanel(taxonomy::Taxonomy),
+    Segment(segment::Segment),
+}
+
+impl Import {
+    pub(crate) fn run(&self) -> Result {
+        match self {
+            Self::Amplitude(x) | Self::Mixpanel(x) => x.run(),
+            Self::Segment(x) => x.run(),
+        }
+    }
+}
+
+/// Options shared by the importers
+#[derive(Debug, Parser)]
+pub struct ImportOpt {
+    /// Folder to write the plan to
+    #[clap(short, long, default_value = "plan")]
+    pub output: PathBuf,
+
+    /// Overwrite existing files
+    #[clap(long)]
+    pub force: bool,
+}
+
+/// Plan being converted, along with what could not be converted
+#[derive(Debug, Default)]
+pub(crate) struct Imported {
+    /// Content of the plan files by their name
+    files: IndexMap<String, Mapping>,
+    unmapped: Vec<String>,
+}
+
+impl Imported {
+    pub(crate) fn metric(
+        &mut self,
+        file: &str,
+        name: &str,
+        description: Option<&str>,
+        sources: &[String],
+    ) -> Result {
+        self.unique("metrics", "metric", name)?;
+        self.insert(file, "metrics", name, item(description, sources));
+        Ok(())
+    }
+
+    pub(crate) fn pageview(
+        &mut self,
+        file: &str,
+        name: &str,
+        description: Option<&str>,
+        sources: &[String],
+    ) -> Result {
+        self.unique("pageviews", "pageview", name)?;
+        self.insert(file, "pageviews", name, item(description, sources));
+        Ok(())
+    }
+
+    /// Declare a source in `sources.yaml` unless it already is
+    pub(crate) fn source(&mut self, name: &str, description: Option<&str>) {
+        let declared = self
+            .files
+            .get("sources")
+            .and_then(|spec| spec.get("sources"))
+            .and_then(|sources| sources.get(name))
+            .is_some();
+
+        if !declared {
+            self.insert("sources", "sources", name, item(description, &[]));
+        }
+    }
+
+    /// Error when the name is already in the section of any file, since the
+    /// later one would silently replace it
+    fn unique(&self, section: &str, kind: &str, name: &str) -> Result {
+        let exists = self
+            .files
+            .values()
+            .filter_map(|spec| spec.get(section))
+            .any(|items| items.get(name).is_some());
+
+        if exists {
+            return Err(eyre!(
+                "{kind} `{name}` is defined more than once in the export"
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn insert(&mut self, file: &str, section: &str, name: &str, value: Value) {
+        let spec = self.files.entry(file.to_string()).or_default();
+
+        if let Value::Mapping(items) = spec
+            .entry(section.into())
+            .or_insert_with(|| Mapping::new().into())
+        {
+            items.insert(name.into(), value);
+        }
+    }
+
+    /// Record something which has no equivalent in a plan
+    pub(crate) fn unmapped(&mut self, message: String) {
+        self.unmapped.push(message);
+    }
+
+    /// Write the plan files and report what was left out
+    pub(crate) fn write(self, opt: &ImportOpt) -> Result {
+        if self.files.is_empty() {
+            return Err(eyre!("nothing to import"));
+        }
+
+        let files = self
+            .files
+            .into_iter()
+            .map(|(name, spec)| {
+                let content = yaml::to_string(&spec.into(), Comments::default());
+                (opt.output.join(format!("{name}.yaml")), content)
+            })
+            .collect::<Vec<_>>();
+
+        // Check everything before writing so that a failure leaves nothing behind
+        if !opt.force
+            && let Some((path, _)) = files.iter().find(|(path, _)| path.exists())
+        {
+            return Err(eyre!(
+                "{} already exists, use --force to overwrite it",
+                path.display()
+            ));
+        }
+
+        create_dir_all(&opt.output)?;
+
+        for (path, content) in files {
+            write(&path, content)?;
+            println!("{} {}", "created".green(), path.display());
+        }
+
+        if !self.unmapped.is_empty() {
+            println!(
+                "\n{} unable to map these to the plan:",
+                "warning:".yellow().bold()
+            );
+
+            for message in &self.unmapped {
+                println!("  {} {message}", "-".yellow());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn item(description: Option<&str>, sources: &[String]) -> Value {
+    let mut item = Mapping::new();
+
+    if let Some(description) = description.map(str::trim).filter(|d| !d.is_empty()) {
+        item.insert("description".into(), description.into());
+    }
+
+    if !sources.is_empty() {
+        item.insert("sources".into(), sources.to_vec().into());
+    }
+
+    item.into()
+}
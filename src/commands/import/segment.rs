@@ -0,0 +1,170 @@
+use std::{fs::read_to_string, path::PathBuf};
+
+use clap::Parser;
+use eyre::eyre;
+use serde_json::Value;
+use tracing::{debug, instrument};
+
+use crate::{
+    commands::{
+        generate::catalog::slug,
+        import::{ImportOpt, Imported},
+    },
+    error::Result,
+};
+
+/// Import a Segment Protocols tracking plan export
+#[derive(Debug, Parser)]
+pub struct Segment {
+    /// Tracking plan JSON, from the Config API or the Public API
+    pub file: PathBuf,
+
+    /// Label to split the plan into files by, falling back to `metrics.yaml`
+    /// and `pageviews.yaml` for the items without it
+    #[clap(long, default_value = "category")]
+    pub split_by: String,
+
+    #[clap(flatten)]
+    pub opt: ImportOpt,
+}
+
+impl Segment {
+    #[instrument(name = "segment", skip_all)]
+    pub(crate) fn run(&self) -> Result {
+        let content = read_to_string(&self.file)?;
+        let plan: Value = serde_json::from_str(&content)
+            .map_err(|e| eyre!("unable to parse {}: {e}", self.file.display()))?;
+
+        let mut imported = Imported::default();
+
+        match plan.get("rules") {
+            // Public API: one rule per event, page, trait set or common properties
+            Some(Value::Array(rules)) => {
+                debug!("Importing {} Public API rules", rules.len());
+
+                for rule in rules {
+                    self.public_rule(rule, &mut imported)?;
+                }
+            }
+            // Config API: events along with global and trait rules
+            Some(Value::Object(rules)) => {
+                let events = rules.get("events").and_then(Value::as_array);
+                debug!("Importing {} Config API events", events.map_or(0, Vec::len));
+
+                for event in events.into_iter().flatten() {
+                    self.config_event(event, &mut imported)?;
+                }
+
+                for key in ["global", "identify_traits", "group_traits"] {
+                    if rules.get(key).is_some_and(has_properties) {
+                        imported.unmapped(format!("`{key}` rules"));
+                    }
+                }
+            }
+            _ => return Err(eyre!("unable to find the rules of the tracking plan")),
+        }
+
+        imported.write(&self.opt)
+    }
+
+    fn public_rule(&self, rule: &Value, imported: &mut Imported) -> Result {
+        let kind = rule.get("type").and_then(Value::as_str).unwrap_or_default();
+        let schema = rule.get("jsonSchema").unwrap_or(&Value::Null);
+        let description = description(rule).or_else(|| description(schema));
+
+        let Some(name) = rule.get("key").and_then(Value::as_str) else {
+            if kind != "COMMON" || has_properties(schema) {
+                imported.unmapped(format!("`{kind}` rule without a key"));
+            }
+
+            return Ok(());
+        };
+
+        match kind {
+            "TRACK" => {
+                let file = self.file_for(rule, "metrics");
+                imported.metric(&file, name, description, &[])?;
+                unmapped_properties(imported, "metric", name, schema);
+            }
+            "PAGE" | "SCREEN" => {
+                let file = self.file_for(rule, "pageviews");
+                imported.pageview(&file, name, description, &[])?;
+                unmapped_properties(imported, "pageview", name, schema);
+            }
+            _ => imported.unmapped(format!("`{kind}` rule `{name}`")),
+        }
+
+        Ok(())
+    }
+
+    fn config_event(&self, event: &Value, imported: &mut Imported) -> Result {
+        let Some(name) = event.get("name").and_then(Value::as_str) else {
+            imported.unmapped("event without a name".to_string());
+            return Ok(());
+        };
+
+        let schema = event.get("rules").unwrap_or(&Value::Null);
+        let description = description(event).or_else(|| description(schema));
+
+        let file = self.file_for(event, "metrics");
+        imported.metric(&file, name, description, &[])?;
+        unmapped_properties(imported, "metric", name, schema);
+
+        Ok(())
+    }
+
+    /// File an event goes to, from the value of its split label
+    fn file_for(&self, item: &Value, default: &str) -> String {
+        let labels = item.get("labels");
+
+        // Labels are either an object or a list of key and value pairs
+        let value = match labels {
+            Some(Value::Object(labels)) => labels.get(&self.split_by).and_then(Value::as_str),
+            Some(Value::Array(labels)) => labels
+                .iter()
+                .find(|l| l.get("key").and_then(Value::as_str) == Some(&self.split_by))
+                .and_then(|l| l.get("value"))
+                .and_then(Value::as_str),
+            _ => None,
+        };
+
+        match value.map(slug).filter(|v| !v.is_empty()) {
+            Some(value) => value,
+            None => default.to_string(),
+        }
+    }
+}
+
+fn description(value: &Value) -> Option<&str> {
+    value.get("description").and_then(Value::as_str)
+}
+
+/// Properties of the event payload, which Segment nests under `properties`
+fn properties(schema: &Value) -> impl Iterator<Item = &String> {
+    schema
+        .pointer("/properties/properties/properties")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flat_map(|properties| properties.keys())
+}
+
+fn has_properties(schema: &Value) -> bool {
+    properties(schema).next().is_some()
+        || schema
+            .pointer("/properties/traits/properties")
+            .and_then(Value::as_object)
+            .is_some_and(|traits| !traits.is_empty())
+}
+
+fn unmapped_properties(imported: &mut Imported, kind: &str, name: &str, schema: &Value) {
+    let properties = properties(schema)
+        .map(|p| format!("`{p}`"))
+        .collect::<Vec<_>>();
+
+    if !properties.is_empty() {
+        imported.unmapped(format!(
+            "properties of {kind} `{name}`: {}",
+            properties.join(", ")
+        ));
+    }
+}
@@ -0,0 +1,144 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use csv::{ReaderBuilder, StringRecord, Trim};
+use eyre::eyre;
+use indexmap::IndexMap;
+use tracing::{debug, instrument};
+
+use crate::{
+    commands::{
+        generate::catalog::slug,
+        import::{ImportOpt, Imported},
+    },
+    error::Result,
+};
+
+/// Headers the columns are known under, compared ignoring case. Amplitude Data
+/// and Mixpanel Lexicon both export properties along with events, which the
+/// type column tells apart.
+const NAME: &[&str] = &[
+    "event name",
+    "event type",
+    "event",
+    "object name",
+    "entity name",
+    "name",
+];
+const DESCRIPTION: &[&str] = &[
+    "event description",
+    "object description",
+    "entity description",
+    "description",
+];
+const CATEGORY: &[&str] = &["event category", "category", "entity tags", "tags"];
+const PLATFORM: &[&str] = &["platform", "platforms", "event source", "source", "sources"];
+const TYPE: &[&str] = &["object type", "entity type", "type"];
+
+/// Import an Amplitude Data or Mixpanel Lexicon taxonomy CSV export
+#[derive(Debug, Parser)]
+pub struct Taxonomy {
+    /// Taxonomy CSV export, with a header row
+    pub file: PathBuf,
+
+    #[clap(flatten)]
+    pub opt: ImportOpt,
+}
+
+/// Positions of the known columns in a row
+struct Columns {
+    name: usize,
+    description: Option<usize>,
+    category: Option<usize>,
+    platform: Option<usize>,
+    kind: Option<usize>,
+}
+
+impl Columns {
+    fn new(headers: &StringRecord) -> Result<Self> {
+        let find = |names: &[&str]| {
+            names.iter().find_map(|name| {
+                headers
+                    .iter()
+                    .position(|header| header.eq_ignore_ascii_case(name))
+            })
+        };
+
+        Ok(Self {
+            name: find(NAME).ok_or(eyre!("unable to find the event name column"))?,
+            description: find(DESCRIPTION),
+            category: find(CATEGORY),
+            platform: find(PLATFORM),
+            kind: find(TYPE),
+        })
+    }
+}
+
+impl Taxonomy {
+    #[instrument(name = "taxonomy", skip_all)]
+    pub(crate) fn run(&self) -> Result {
+        let mut reader = ReaderBuilder::new()
+            .trim(Trim::All)
+            .flexible(true)
+            .from_path(&self.file)
+            .map_err(|e| eyre!("unable to read {}: {e}", self.file.display()))?;
+
+        let columns = Columns::new(reader.headers()?)?;
+        let mut imported = Imported::default();
+        let mut skipped = IndexMap::<String, usize>::new();
+
+        for record in reader.records() {
+            let record = record?;
+            let get = |column: Option<usize>| {
+                column
+                    .and_then(|c| record.get(c))
+                    .filter(|value| !value.is_empty())
+            };
+
+            let kind = get(columns.kind).unwrap_or("Event");
+            if !kind.eq_ignore_ascii_case("event") {
+                *skipped.entry(kind.to_string()).or_default() += 1;
+                continue;
+            }
+
+            let Some(name) = get(Some(columns.name)) else {
+                *skipped.entry("unnamed event".to_string()).or_default() += 1;
+                continue;
+            };
+
+            let mut sources = vec![];
+            for platform in get(columns.platform).into_iter().flat_map(values) {
+                let source = slug(platform);
+
+                if !source.is_empty() && !sources.contains(&source) {
+                    imported.source(&source, Some(&format!("{platform} platform")));
+                    sources.push(source);
+                }
+            }
+
+            let file = get(columns.category)
+                .and_then(|category| values(category).next())
+                .map(slug)
+                .filter(|category| !category.is_empty())
+                .unwrap_or_else(|| "metrics".to_string());
+
+            imported.metric(&file, name, get(columns.description), &sources)?;
+        }
+
+        debug!("Skipped rows: {:?}", skipped);
+
+        for (kind, count) in skipped {
+            imported.unmapped(format!("`{kind}` rows: {count}"));
+        }
+
+        imported.write(&self.opt)
+    }
+}
+
+/// Values of a list cell, which the exports separate with commas, semicolons
+/// or pipes
+fn values(cell: &str) -> impl Iterator<Item = &str> {
+    cell.split([',', ';', '|'])
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
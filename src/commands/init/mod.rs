@@ -0,0 +1,234 @@
+use std::{
+    fmt::Write as _,
+    fs::{create_dir_all, write},
+    path::{Path, PathBuf},
+};
+
+use anstream::println;
+use clap::{Parser, ValueEnum};
+use dialoguer::{Confirm, MultiSelect, Select};
+use eyre::eyre;
+use owo_colors::OwoColorize;
+use tracing::instrument;
+
+use crate::{
+    commands::lint::rules::{RulesConfig, uses_name_case::Case},
+    config::CONFIG_FILE,
+    error::Result,
+};
+
+/// Sources offered in the interactive mode along with their descriptions
+const PLATFORMS: [(&str, &str); 5] = [
+    ("web", "Web application"),
+    ("ios", "iOS application"),
+    ("android", "Android application"),
+    ("desktop", "Desktop application"),
+    ("server", "Backend server"),
+];
+
+/// Create a starter plan
+#[derive(Debug, Parser)]
+pub struct Init {
+    /// Folder to create the plan in
+    #[clap(default_value = ".")]
+    pub dir: PathBuf,
+
+    /// Ask which sources to declare and which naming case to enforce
+    #[clap(short, long)]
+    pub interactive: bool,
+
+    /// Sources to declare
+    #[clap(long, value_delimiter = ',', default_value = "web,ios,android")]
+    pub sources: Vec<String>,
+
+    /// Case to enforce on metric and pageview names
+    #[clap(long, value_enum, default_value = "title_case")]
+    pub case: Case,
+
+    /// Also create an `ods.toml` in the current folder pointing at the plan
+    #[clap(long)]
+    pub config: bool,
+
+    /// Overwrite existing files
+    #[clap(long)]
+    pub force: bool,
+}
+
+impl Init {
+    #[instrument(name = "init", skip_all)]
+    pub(crate) fn run(&self) -> Result {
+        let (sources, case, config) = if self.interactive {
+            self.ask()?
+        } else {
+            (self.sources.clone(), self.case.clone(), self.config)
+        };
+
+        if sources.is_empty() {
+            return Err(eyre!("at least one source is needed"));
+        }
+
+        if let Some(source) = sources.iter().find(|s| !is_valid_source(s)) {
+            return Err(eyre!("invalid source name `{source}`"));
+        }
+
+        let mut files = vec![
+            (self.dir.join("sources.yaml"), sources_file(&sources)),
+            (self.dir.join("metrics.yaml"), metrics_file(&sources, &case)),
+            (
+                self.dir.join("pageviews.yaml"),
+                pageviews_file(&sources, &case),
+            ),
+            (self.dir.join("lints.yaml"), lints_file(&case)),
+        ];
+
+        if config {
+            files.push((PathBuf::from(CONFIG_FILE), config_file(&self.dir)));
+        }
+
+        // Check everything before writing so that a failure leaves nothing behind
+        if !self.force
+            && let Some((path, _)) = files.iter().find(|(path, _)| path.exists())
+        {
+            return Err(eyre!(
+                "{} already exists, use --force to overwrite it",
+                path.display()
+            ));
+        }
+
+        create_dir_all(&self.dir)?;
+
+        for (path, content) in files {
+            write(&path, content)?;
+            println!("{} {}", "created".green(), path.display());
+        }
+
+        Ok(())
+    }
+
+    /// Prompt for the sources, case and config, defaulting to the flags
+    fn ask(&self) -> Result<(Vec<String>, Case, bool)> {
+        let mut items = PLATFORMS
+            .iter()
+            .map(|(name, _)| name.to_string())
+            .collect::<Vec<_>>();
+
+        // Keep sources given on the command line which are not in the list
+        for source in &self.sources {
+            if !items.contains(source) {
+                items.push(source.clone());
+            }
+        }
+
+        let defaults = items
+            .iter()
+            .map(|item| self.sources.contains(item))
+            .collect::<Vec<_>>();
+
+        let selected = MultiSelect::new()
+            .with_prompt("Which platforms do you collect data from?")
+            .items(&items)
+            .defaults(&defaults)
+            .interact()?;
+
+        let cases = Case::value_variants();
+
+        let case = Select::new()
+            .with_prompt("Which case should metric and pageview names be in?")
+            .items(cases.iter().map(ToString::to_string))
+            .default(
+                cases
+                    .iter()
+                    .position(|c| c.to_string() == self.case.to_string())
+                    .unwrap_or_default(),
+            )
+            .interact()?;
+
+        let config = Confirm::new()
+            .with_prompt(format!("Create an {CONFIG_FILE}?"))
+            .default(self.config)
+            .interact()?;
+
+        Ok((
+            selected.into_iter().map(|i| items[i].clone()).collect(),
+            cases[case].clone(),
+            config,
+        ))
+    }
+}
+
+/// Source names are written unquoted into the YAML files
+fn is_valid_source(source: &str) -> bool {
+    !source.is_empty()
+        && source
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+fn description(source: &str) -> String {
+    PLATFORMS
+        .iter()
+        .find(|(name, _)| *name == source)
+        .map_or_else(|| format!("{source} source"), |(_, d)| d.to_string())
+}
+
+fn case_name(case: &Case) -> String {
+    case.to_possible_value()
+        .expect("Case has no skipped variants")
+        .get_name()
+        .to_string()
+}
+
+fn sources_file(sources: &[String]) -> String {
+    let mut content = "sources:\n".to_string();
+
+    for source in sources {
+        let _ = writeln!(
+            content,
+            "  {source}:\n    description: {}",
+            description(source)
+        );
+    }
+
+    content
+}
+
+fn metrics_file(sources: &[String], case: &Case) -> String {
+    format!(
+        "metrics:\n  {}:\n    description: User logs into the application\n    sources: [{}]\n",
+        case.convert("User Logged In"),
+        sources.join(", ")
+    )
+}
+
+fn pageviews_file(sources: &[String], case: &Case) -> String {
+    format!(
+        "pageviews:\n  {}:\n    description: Landing page of the application\n    sources: [{}]\n",
+        case.convert("Home Page"),
+        sources.join(", ")
+    )
+}
+
+fn lints_file(case: &Case) -> String {
+    let mut content = "lint:\n".to_string();
+
+    for (rule, description, level) in RulesConfig::defaults() {
+        let level = serde_json::to_string(&level).expect("Failed to serialize LintLevel");
+        let _ = writeln!(
+            content,
+            "  # {description}\n  {rule}:\n    level: {}",
+            level.trim_matches('"')
+        );
+
+        if rule == "uses_name_case" {
+            let case = case_name(case);
+            let _ = writeln!(content, "    metric: {case}\n    pageview: {case}");
+        }
+    }
+
+    content
+}
+
+fn config_file(dir: &Path) -> String {
+    let plan = toml::Value::String(dir.to_string_lossy().replace('\\', "/"));
+    format!("plan = {plan}\n")
+}
@@ -0,0 +1,88 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{read_to_string, write},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{from_str, to_string, to_vec};
+use tracing::{debug, trace};
+
+use crate::{
+    commands::lint::rules::{RuleResults, RulesConfig},
+    error::Result,
+    schema::spec::Spec,
+};
+
+/// Name of the cache file, written next to the plan by default
+pub(crate) const CACHE_FILE: &str = ".odscache";
+
+/// Results of the per file rules from previous runs, stored on disk
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct LintCache {
+    version: String,
+    files: HashMap<String, CacheEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    key: String,
+    results: Vec<RuleResults>,
+}
+
+impl LintCache {
+    /// Read the cache, starting afresh if it is missing or from another version
+    pub(crate) fn load(path: &Path) -> Self {
+        let cache = read_to_string(path)
+            .ok()
+            .and_then(|content| from_str::<Self>(&content).ok())
+            .filter(|cache| cache.version == env!("CARGO_PKG_VERSION"));
+
+        match cache {
+            Some(cache) => {
+                debug!("Loaded lint cache with {} files", cache.files.len());
+                cache
+            }
+            None => Self {
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                files: HashMap::new(),
+            },
+        }
+    }
+
+    pub(crate) fn save(&self, path: &Path) -> Result<()> {
+        write(path, to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Compute the key under which the results of the spec are stored
+    pub(super) fn key(spec: &Spec, rules_config: &RulesConfig, strict: bool) -> Result<String> {
+        let mut hasher = blake3::Hasher::new();
+
+        hasher.update(env!("CARGO_PKG_VERSION").as_bytes());
+        hasher.update(spec.hash.as_bytes());
+        hasher.update(&[strict.into()]);
+        hasher.update(&to_vec(rules_config)?);
+
+        Ok(hasher.finalize().to_hex().to_string())
+    }
+
+    pub(super) fn get(&self, name: &str, key: &str) -> Option<Vec<RuleResults>> {
+        let entry = self.files.get(name).filter(|entry| entry.key == key)?;
+
+        trace!("Using cached results for file: {}", name);
+        Some(entry.results.clone())
+    }
+
+    pub(super) fn insert(&mut self, name: &str, key: String, results: Vec<RuleResults>) {
+        self.files
+            .insert(name.to_string(), CacheEntry { key, results });
+    }
+
+    /// Forget the files which are no longer in the plan
+    pub(crate) fn retain<'a>(&mut self, names: impl IntoIterator<Item = &'a String>) {
+        let names = names.into_iter().collect::<HashSet<_>>();
+
+        self.files.retain(|name, _| names.contains(&name));
+    }
+}
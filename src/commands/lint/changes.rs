@@ -0,0 +1,131 @@
+use std::{collections::HashSet, fs::read_to_string, path::Path};
+
+use indexmap::IndexMap;
+use serde_json::Value;
+use tracing::trace;
+
+use crate::{
+    commands::lint::{LintItem, SpecResults},
+    error::Result,
+    schema::{LINTS_FILES, git::ChangedFile, spec::Spec},
+};
+
+/// Parts of a plan changed since a git revision
+#[derive(Debug)]
+pub(super) struct Changes {
+    /// Changed files, with the top level keys and items changed in them, or
+    /// `None` when everything in the file is reported
+    files: IndexMap<String, Option<HashSet<(String, String)>>>,
+}
+
+impl Changes {
+    /// Compare the loaded plan with the content of the changed files at the revision
+    pub(super) fn new(
+        files: &IndexMap<String, Spec>,
+        changed: IndexMap<String, ChangedFile>,
+    ) -> Result<Self> {
+        // The lints file applies to the whole plan
+        if changed
+            .keys()
+            .any(|name| LINTS_FILES.contains(&name.as_str()) && files.contains_key(name))
+        {
+            trace!("Lints file changed, reporting the whole plan");
+
+            return Ok(Self {
+                files: files.keys().map(|name| (name.clone(), None)).collect(),
+            });
+        }
+
+        let mut changes = IndexMap::new();
+
+        for (name, file) in changed {
+            if !files.contains_key(&name) {
+                continue;
+            }
+
+            let new = parse(&file.path, &read_to_string(&file.path)?);
+            let old = file.old.map(|old| parse(&file.path, &old));
+
+            // Report everything in new files and in files whose old version
+            // can not be compared with
+            let items = match (old, new) {
+                (Some(Ok(old)), Ok(new)) => changed_keys(&old, &new),
+                _ => None,
+            };
+
+            changes.insert(name, items);
+        }
+
+        Ok(Self { files: changes })
+    }
+
+    pub(super) fn files(&self) -> Vec<String> {
+        self.files.keys().cloned().collect()
+    }
+
+    /// Drop the findings on the parts of the files which did not change
+    pub(super) fn retain(&self, results: &mut IndexMap<String, SpecResults>) {
+        results.retain(|name, _| self.files.contains_key(name));
+
+        for (name, spec_results) in results.iter_mut() {
+            let Some(Some(keys)) = self.files.get(name) else {
+                continue;
+            };
+
+            for (ty, ty_results) in spec_results.iter_mut() {
+                ty_results.retain(|item, _| match ty {
+                    LintItem::Metric => keys.contains(&("metrics".to_string(), item.clone())),
+                    LintItem::Pageview => keys.contains(&("pageviews".to_string(), item.clone())),
+                    LintItem::Source => keys.contains(&("sources".to_string(), item.clone())),
+                    LintItem::Key => keys.iter().any(|(key, item_name)| {
+                        let prefix = if item_name.is_empty() {
+                            key.clone()
+                        } else {
+                            format!("{key}.{item_name}")
+                        };
+
+                        item == &prefix || item.starts_with(&format!("{prefix}."))
+                    }),
+                });
+            }
+
+            spec_results.retain(|_, ty_results| !ty_results.is_empty());
+        }
+    }
+}
+
+fn parse(path: &Path, content: &str) -> Result<Value> {
+    Ok(match path.extension().and_then(|e| e.to_str()) {
+        Some("json") => serde_json::from_str(content)?,
+        _ => serde_yml::from_str(content)?,
+    })
+}
+
+/// Top level keys and items which differ between the versions of a file.
+/// Keys are paired with an empty name while items are paired with theirs.
+fn changed_keys(old: &Value, new: &Value) -> Option<HashSet<(String, String)>> {
+    // Rule configuration in the file applies to everything in it
+    if old.get("lint") != new.get("lint") {
+        return None;
+    }
+
+    let mut keys = HashSet::new();
+
+    for (key, value) in new.as_object().into_iter().flatten() {
+        match (key.as_str(), value.as_object()) {
+            ("metrics" | "pageviews" | "sources", Some(items)) => {
+                for (name, item) in items {
+                    if old.get(key).and_then(|o| o.get(name)) != Some(item) {
+                        keys.insert((key.clone(), name.clone()));
+                    }
+                }
+            }
+            _ if old.get(key) != Some(value) => {
+                keys.insert((key.clone(), String::new()));
+            }
+            _ => {}
+        }
+    }
+
+    Some(keys)
+}
@@ -4,23 +4,33 @@ use std::{
 };
 
 use anstream::println;
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use eyre::eyre;
+use indexmap::IndexMap;
 use owo_colors::OwoColorize;
 use proc_exit::Code;
+use rayon::prelude::*;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use serde_json::to_string;
+use serde_json::{to_string, to_string_pretty};
 use tracing::{debug, instrument, trace};
 
 use crate::{
-    commands::lint::rules::Rules,
+    commands::lint::{
+        cache::{CACHE_FILE, LintCache},
+        changes::Changes,
+        rules::{Rules, RulesConfig},
+    },
+    config::Config,
     error::{Result, exit},
-    schema::SchemaOpt,
+    schema::{SchemaOpt, lints_file, spec::Spec},
 };
 
+pub(crate) mod cache;
+mod changes;
 pub mod rules;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum LintLevel {
     Off,
@@ -33,10 +43,11 @@ pub enum LintLevel {
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
 #[serde(rename_all = "snake_case")]
-enum LintItem {
+pub(crate) enum LintItem {
     Metric,
     Pageview,
     Source,
+    Key,
 }
 
 impl FmtDisplay for LintItem {
@@ -51,11 +62,31 @@ impl FmtDisplay for LintItem {
     }
 }
 
-#[derive(Debug)]
-struct LintResult {
-    message: String,
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub(crate) struct LintResult {
+    pub(crate) message: String,
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum LintFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+/// Single lint finding in the JSON report
+#[derive(Debug, Serialize)]
+struct Finding<'a> {
+    file: &'a str,
+    item: LintItem,
+    name: &'a str,
+    level: LintLevel,
+    message: &'a str,
+}
+
+pub(crate) type SpecResults = IndexMap<LintItem, IndexMap<String, Vec<(LintLevel, LintResult)>>>;
+
 /// Lint plan
 #[derive(Debug, Parser)]
 pub struct Lint {
@@ -65,20 +96,109 @@ pub struct Lint {
     /// File paths in the plan folder to lint (defaults to all)
     pub files: Vec<PathBuf>,
 
+    /// File path in the plan folder to lint, for when the plan comes from
+    /// `ods.toml` or `--plan`
+    #[clap(long = "file", value_name = "FILE")]
+    pub file_flags: Vec<PathBuf>,
+
+    /// Only report on the plan files and items changed since a git revision
+    #[clap(long, value_name = "REV", conflicts_with_all = ["files", "file_flags"])]
+    pub changed_since: Option<String>,
+
     /// Exit with a zero code even on lint errors
-    #[clap(long)]
+    #[clap(long, overrides_with = "fail")]
     pub no_fail: bool,
+
+    /// Exit with a non-zero code on lint errors, even when `ods.toml` sets
+    /// `no_fail`
+    #[clap(long, overrides_with = "no_fail")]
+    pub fail: bool,
+
+    /// Format of the lint report [default: text]
+    #[clap(long, value_enum)]
+    pub format: Option<LintFormat>,
+
+    /// Lint again whenever a plan file changes, only running the per file
+    /// rules on the changed files
+    #[clap(long)]
+    pub watch: bool,
+
+    /// Reuse the results of unchanged files from previous runs
+    #[clap(long)]
+    pub cache: bool,
+
+    /// Path to the cache file [default: .odscache next to the plan]
+    #[clap(long, requires = "cache")]
+    pub cache_location: Option<PathBuf>,
+
+    /// Rule configuration from `ods.toml` overriding the lints file
+    #[clap(skip)]
+    pub(crate) rules: Option<RulesConfig>,
 }
 
 impl Lint {
+    pub(crate) fn apply(&mut self, config: &Config) {
+        self.files.append(&mut self.file_flags);
+        self.schema.apply(config);
+
+        if !self.fail {
+            self.no_fail = self.no_fail || config.lint.no_fail.unwrap_or_default();
+        }
+        self.format = self.format.or(config.lint.format);
+        self.rules = config.lint.rules.clone();
+    }
+
     #[instrument(name = "lint", skip_all)]
     pub(crate) fn run(&self) -> Result {
+        let mut lint_cache = match self.cache {
+            true => Some(LintCache::load(&self.cache_path()?)),
+            false => None,
+        };
+
+        if self.watch {
+            // The results of unchanged files are kept between runs even
+            // without `--cache`, so only the changed files are linted again
+            let mut lint_cache = lint_cache.unwrap_or_default();
+
+            return self
+                .schema
+                .watch(None, || self.lint(Some(&mut lint_cache)).map(|_| ()));
+        }
+
+        if self.lint(lint_cache.as_mut())? > 0 && !self.no_fail {
+            exit(Code::FAILURE);
+        }
+
+        Ok(())
+    }
+
+    /// Path of the cache file, next to the plan unless given
+    fn cache_path(&self) -> Result<PathBuf> {
+        if let Some(path) = &self.cache_location {
+            return Ok(path.clone());
+        }
+
+        // If the plan is a file, cache next to it in the parent folder
+        let plan_path = absolute(self.schema.plan()?)?;
+
+        Ok(match plan_path.is_file() {
+            true => plan_path.with_file_name(CACHE_FILE),
+            false => plan_path.join(CACHE_FILE),
+        })
+    }
+
+    /// Lint the plan, print the report and return the number of errors
+    fn lint(&self, mut lint_cache: Option<&mut LintCache>) -> Result<usize> {
         let mut warnings = 0;
         let mut errors = 0;
 
         let files = self.schema.load()?;
 
-        let plan_path = absolute(&self.schema.plan)?;
+        if let Some(lint_cache) = lint_cache.as_mut() {
+            lint_cache.retain(files.keys());
+        }
+
+        let plan_path = absolute(self.schema.plan()?)?;
 
         // Filter files to lint based on user input
         let selected = if self.files.is_empty() {
@@ -88,7 +208,7 @@ impl Lint {
                 .files
                 .iter()
                 .map(|file| {
-                    let file_path = absolute(&file)?;
+                    let file_path = absolute(file)?;
 
                     if !file.exists() {
                         return Err(eyre!("unable to find {}", file.display()));
@@ -114,20 +234,64 @@ impl Lint {
                 .collect()
         };
 
-        // Check if lint config file exists
-        let lint_file_config = selected
-            .iter()
-            .find(|(name, _)| ["lints.json", "lints.yaml", "lints.yml"].contains(&name.as_str()))
-            .and_then(|(_, spec)| spec.lint.as_ref());
+        // The whole plan is still checked so that the cross file rules see it
+        let changes = match &self.changed_since {
+            Some(rev) => Some(Changes::new(&selected, self.schema.changed_since(rev)?)?),
+            None => None,
+        };
+        let changed_files = changes.as_ref().map(Changes::files);
 
-        // Compute cache
-        let cache = Rules::pre_compute(selected.iter().map(|(_, spec)| spec).collect())?;
+        let strict = self.schema.is_strict(&selected);
 
-        // Lint each file
-        for (name, spec) in &selected {
-            debug!("Linting file: {}", name);
-            let spec_results = Rules::run(&cache, lint_file_config, spec)?;
+        let mut results = check(
+            &selected,
+            changed_files.as_deref(),
+            strict,
+            self.rules.as_ref(),
+            lint_cache.as_deref_mut(),
+        )?;
+
+        if let Some(changes) = &changes {
+            changes.retain(&mut results);
+        }
+
+        if self.cache
+            && let Some(lint_cache) = lint_cache
+        {
+            lint_cache.save(&self.cache_path()?)?;
+        }
 
+        if self.format.unwrap_or_default() == LintFormat::Json {
+            let findings = results
+                .iter()
+                .flat_map(|(file, spec_results)| {
+                    spec_results.iter().flat_map(move |(item, ty_results)| {
+                        ty_results.iter().flat_map(move |(name, results)| {
+                            results
+                                .iter()
+                                .filter(|(level, _)| *level != LintLevel::Off)
+                                .map(move |(level, result)| Finding {
+                                    file,
+                                    item: *item,
+                                    name,
+                                    level: *level,
+                                    message: &result.message,
+                                })
+                        })
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            println!("{}", to_string_pretty(&findings)?);
+
+            return Ok(findings
+                .iter()
+                .filter(|f| f.level == LintLevel::Error)
+                .count());
+        }
+
+        // Lint each file
+        for (name, spec_results) in results {
             if spec_results.is_empty() {
                 trace!("No issues found in file: {}", name);
                 continue;
@@ -164,10 +328,84 @@ impl Lint {
             );
         }
 
-        if errors > 0 && !self.no_fail {
-            exit(Code::FAILURE);
+        Ok(errors)
+    }
+}
+
+/// Run the lint rules over the files of a plan, only linting the selected
+/// ones if given
+pub(crate) fn check(
+    files: &IndexMap<String, Spec>,
+    selected: Option<&[String]>,
+    strict: bool,
+    overrides: Option<&RulesConfig>,
+    mut lint_cache: Option<&mut LintCache>,
+) -> Result<IndexMap<String, SpecResults>> {
+    let mut all_results = IndexMap::new();
+
+    // Check if lint config file exists
+    let lint_file_config = lints_file(files).and_then(|spec| spec.lint.as_ref());
+
+    // Rules given in the project config take precedence over the lints file
+    let lint_file_config = match (overrides, lint_file_config) {
+        (Some(overrides), Some(common)) => Some(overrides.clone().base_upon(common)),
+        (overrides, common) => overrides.or(common).cloned(),
+    };
+
+    // Compute cache
+    let cache = Rules::pre_compute(files.iter().map(|(_, spec)| spec).collect())?;
+
+    // Lint files in parallel, collecting in the order of the files
+    let files_results = files
+        .par_iter()
+        .filter(|(name, _)| selected.is_none_or(|selected| selected.contains(name)))
+        .map(|(name, spec)| {
+            debug!("Linting file: {}", name);
+            let rules_config = Rules::config(lint_file_config.as_ref(), spec);
+
+            // Only the rules which look at a single file can be cached
+            let key = LintCache::key(spec, &rules_config, strict)?;
+            let cached = lint_cache.as_deref().and_then(|c| c.get(name, &key));
+
+            let (mut rule_results, new_entry) = match cached {
+                Some(rule_results) => (rule_results, None),
+                None => {
+                    let rule_results = Rules::run(&cache, &rules_config, spec, false)?;
+                    (rule_results.clone(), Some((key, rule_results)))
+                }
+            };
+
+            rule_results.extend(Rules::run(&cache, &rules_config, spec, true)?);
+
+            let mut spec_results = Rules::collect(rule_results);
+
+            if strict {
+                for key in &spec.unknown_keys {
+                    spec_results
+                        .entry(LintItem::Key)
+                        .or_default()
+                        .entry(key.to_string())
+                        .or_default()
+                        .push((
+                            LintLevel::Error,
+                            LintResult {
+                                message: key.message(),
+                            },
+                        ));
+                }
+            }
+
+            Ok((name, spec_results, new_entry))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    for (name, spec_results, new_entry) in files_results {
+        if let (Some(lint_cache), Some((key, rule_results))) = (lint_cache.as_mut(), new_entry) {
+            lint_cache.insert(name, key, rule_results);
         }
 
-        Ok(())
+        all_results.insert(name.clone(), spec_results);
     }
+
+    Ok(all_results)
 }
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use indexmap::IndexMap;
 
 use crate::{error::Result, schema::spec::Spec};
 
@@ -12,9 +12,9 @@ impl CommonCache {
         self.sources.extend(
             spec.sources
                 .as_ref()
-                .unwrap_or(&HashMap::new())
-                .iter()
-                .map(|(name, _)| name.clone()),
+                .unwrap_or(&IndexMap::new())
+                .keys()
+                .cloned(),
         );
 
         Ok(())
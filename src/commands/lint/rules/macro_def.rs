@@ -1,5 +1,5 @@
 macro_rules! rules {
-    ($($rule:ident,)+) => {
+    ($($(#[doc = $doc:literal])* $rule:ident,)+) => {
         $(pub mod $rule;)+
 
         #[allow(non_camel_case_types)]
@@ -8,6 +8,14 @@ macro_rules! rules {
             $($rule,)+
         }
 
+        impl Rules {
+            pub(super) fn is_cross_file(&self) -> bool {
+                match self {
+                    $(Rules::$rule => <$rule::Config as Rule>::CROSS_FILE,)+
+                }
+            }
+        }
+
         impl std::fmt::Display for Rules {
             fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
                 match self {
@@ -19,7 +27,7 @@ macro_rules! rules {
         paste::paste! {
             $(
                 #[allow(non_camel_case_types)]
-                #[derive(Debug, Clone, Default, Deserialize)]
+                #[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
                 pub struct [<$rule _config>] {
                     pub level: Option<LintLevel>,
                     #[serde(flatten)]
@@ -27,9 +35,9 @@ macro_rules! rules {
                 }
             )+
 
-            #[derive(Debug, Clone, Default, Deserialize)]
+            #[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
             pub struct RulesConfig {
-                $(pub $rule: Option<[<$rule _config>]>,)+
+                $($(#[doc = $doc])* pub $rule: Option<[<$rule _config>]>,)+
             }
 
             impl RulesConfig {
@@ -45,6 +53,27 @@ macro_rules! rules {
                         }),)+
                     }
                 }
+
+                /// Name, description and default level of every rule
+                pub(crate) fn defaults() -> Vec<(&'static str, &'static str, LintLevel)> {
+                    vec![$((
+                        stringify!($rule),
+                        concat!($($doc,)*).trim(),
+                        <$rule::Config as Rule>::level(&Default::default()),
+                    ),)+]
+                }
+
+                pub(crate) fn rule_fields(rule: &str) -> Option<Vec<&'static str>> {
+                    match rule {
+                        $(stringify!($rule) => Some(
+                            ["level"]
+                                .into_iter()
+                                .chain(fields::<$rule::Config>().iter().copied())
+                                .collect(),
+                        ),)+
+                        _ => None,
+                    }
+                }
             }
 
             #[derive(Debug, Default)]
@@ -54,13 +83,27 @@ macro_rules! rules {
             }
         }
 
-        impl RulesCache {
-            pub(super) fn pre_compute_rule(&mut self, rule: &Rules, spec: &Spec) -> Result<()> {
-                match rule {
-                    $(Rules::$rule => <$rule::Config as Rule>::pre_compute(&mut self.$rule, spec)?,)+
-                }
+        paste::paste! {
+            impl RulesCache {
+                /// Pre-compute the cache of every rule, with each rule on its own thread
+                pub(super) fn pre_compute_rules(&mut self, specs: &[&Spec]) -> Result<()> {
+                    let RulesCache { $($rule: [<$rule _cache>],)+ .. } = self;
+                    $(let mut [<$rule _result>] = Ok(());)+
 
-                Ok(())
+                    rayon::scope(|s| {
+                        $(s.spawn(|_| {
+                            trace!("Pre-computing for rule: {}", stringify!($rule));
+
+                            [<$rule _result>] = specs.iter().try_for_each(|spec| {
+                                <$rule::Config as Rule>::pre_compute([<$rule _cache>], spec)
+                            });
+                        });)+
+                    });
+
+                    $([<$rule _result>]?;)+
+
+                    Ok(())
+                }
             }
         }
 
@@ -2,16 +2,17 @@ use std::fmt::Debug as FmtDebug;
 
 use clap::ValueEnum;
 use indexmap::IndexMap;
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use tracing::{instrument, trace};
 
 use crate::{
     commands::lint::{
-        LintItem, LintLevel, LintResult,
+        LintItem, LintLevel, LintResult, SpecResults,
         rules::cache::{CommonCache, RuleCache},
     },
     error::Result,
-    schema::spec::Spec,
+    schema::{spec::Spec, strict::fields},
 };
 
 #[macro_use]
@@ -20,12 +21,19 @@ mod macro_def;
 mod cache;
 
 rules! {
+    /// Sources used by metrics and pageviews must be defined
     needs_explicit_sources,
+    /// Metrics must have a description
     needs_metric_description,
+    /// Sources must have a description
     needs_source_description,
+    /// Metric names must be unique across the plan
     no_duplicate_metrics,
+    /// Pageview names must be unique across the plan
     no_duplicate_pageviews,
+    /// Source names must be unique across the plan
     no_duplicate_sources,
+    /// Metric and pageview names must be in the configured case
     uses_name_case,
 }
 
@@ -38,40 +46,62 @@ impl Rules {
             cache._common.pre_compute(spec)?;
         }
 
-        for rule in Rules::value_variants() {
-            trace!("Pre-computing for rule: {}", rule);
-
-            for spec in &specs {
-                cache.pre_compute_rule(rule, spec)?;
-            }
-        }
+        cache.pre_compute_rules(&specs)?;
 
         Ok(cache)
     }
 
-    #[allow(clippy::type_complexity)]
-    #[instrument(name = "run", skip_all)]
-    pub(super) fn run(
-        cache: &RulesCache,
-        lint_file_config: Option<&RulesConfig>,
-        spec: &Spec,
-    ) -> Result<IndexMap<LintItem, IndexMap<String, Vec<(LintLevel, LintResult)>>>> {
-        let mut all_results = IndexMap::new();
-
-        // Merge common and spec lint configurations
-        let rules_config = spec.lint.as_ref().cloned().map_or(
+    /// Merge the lints file configuration into the spec configuration
+    pub(super) fn config(lint_file_config: Option<&RulesConfig>, spec: &Spec) -> RulesConfig {
+        spec.lint.as_ref().cloned().map_or(
             lint_file_config.cloned().unwrap_or_default(),
             |spec_config| {
                 lint_file_config.map_or(spec_config.clone(), |common_config| {
                     spec_config.base_upon(common_config)
                 })
             },
-        );
+        )
+    }
+
+    /// Run either the cross file rules or the rest of them on the spec
+    #[instrument(name = "run", skip_all)]
+    pub(super) fn run(
+        cache: &RulesCache,
+        rules_config: &RulesConfig,
+        spec: &Spec,
+        cross_file: bool,
+    ) -> Result<Vec<RuleResults>> {
+        let mut all_results = vec![];
 
         for rule in Rules::value_variants() {
+            if rule.is_cross_file() != cross_file {
+                continue;
+            }
+
             trace!("Running rule: {}", rule);
             let (level, results) = rules_config.run_rule(rule, cache, spec)?;
 
+            all_results.push(RuleResults {
+                rule: rule.to_string(),
+                level,
+                results,
+            });
+        }
+
+        Ok(all_results)
+    }
+
+    /// Group the results of the rules by item, keeping the order of the rules
+    pub(super) fn collect(mut rule_results: Vec<RuleResults>) -> SpecResults {
+        let mut all_results = IndexMap::new();
+
+        rule_results.sort_by_key(|rule_results| {
+            Rules::value_variants()
+                .iter()
+                .position(|rule| rule.to_string() == rule_results.rule)
+        });
+
+        for RuleResults { level, results, .. } in rule_results {
             for (ty, name, result) in results {
                 all_results
                     .entry(ty)
@@ -82,13 +112,24 @@ impl Rules {
             }
         }
 
-        Ok(all_results)
+        all_results
     }
 }
 
-trait Rule: FmtDebug + Clone + Default + for<'de> Deserialize<'de> {
+/// Results of running a single rule on a spec
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct RuleResults {
+    rule: String,
+    level: LintLevel,
+    results: Vec<(LintItem, String, LintResult)>,
+}
+
+trait Rule: FmtDebug + Clone + Default + for<'de> Deserialize<'de> + JsonSchema {
     type Cache;
 
+    /// Whether the results depend on the other files in the plan
+    const CROSS_FILE: bool = false;
+
     fn level(&self) -> LintLevel {
         LintLevel::Warning
     }
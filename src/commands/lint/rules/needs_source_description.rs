@@ -1,6 +1,6 @@
-use std::collections::HashMap;
-
-use serde::Deserialize;
+use indexmap::IndexMap;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     error::Result,
@@ -11,7 +11,7 @@ use crate::{
     schema::spec::Spec,
 };
 
-#[derive(Debug, Clone, Default, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
 pub struct Config {}
 
 impl Rule for Config {
@@ -24,7 +24,7 @@ impl Rule for Config {
     ) -> Result<Vec<(LintItem, String, LintResult)>> {
         let mut results = vec![];
 
-        for (name, source) in spec.sources.as_ref().unwrap_or(&HashMap::new()) {
+        for (name, source) in spec.sources.as_ref().unwrap_or(&IndexMap::new()) {
             if source.description.is_none() {
                 results.push((
                     LintItem::Source,
@@ -1,6 +1,6 @@
-use std::collections::HashMap;
-
-use serde::Deserialize;
+use indexmap::IndexMap;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     error::Result,
@@ -11,7 +11,7 @@ use crate::{
     schema::spec::Spec,
 };
 
-#[derive(Debug, Clone, Default, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
 pub struct Config {}
 
 #[derive(Debug, Default)]
@@ -22,6 +22,8 @@ pub(super) struct Cache {
 impl Rule for Config {
     type Cache = Cache;
 
+    const CROSS_FILE: bool = true;
+
     fn level(&self) -> LintLevel {
         LintLevel::Error
     }
@@ -30,9 +32,9 @@ impl Rule for Config {
         cache.pageviews.extend(
             spec.pageviews
                 .as_ref()
-                .unwrap_or(&HashMap::new())
-                .iter()
-                .map(|(name, _)| name.clone()),
+                .unwrap_or(&IndexMap::new())
+                .keys()
+                .cloned(),
         );
 
         Ok(())
@@ -45,7 +47,7 @@ impl Rule for Config {
     ) -> Result<Vec<(LintItem, String, LintResult)>> {
         let mut results = vec![];
 
-        for name in spec.pageviews.as_ref().unwrap_or(&HashMap::new()).keys() {
+        for name in spec.pageviews.as_ref().unwrap_or(&IndexMap::new()).keys() {
             if cache.rule.pageviews.iter().filter(|s| *s == name).count() > 1 {
                 results.push((
                     LintItem::Pageview,
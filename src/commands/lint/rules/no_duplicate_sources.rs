@@ -1,6 +1,6 @@
-use std::collections::HashMap;
-
-use serde::Deserialize;
+use indexmap::IndexMap;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     error::Result,
@@ -11,12 +11,14 @@ use crate::{
     schema::spec::Spec,
 };
 
-#[derive(Debug, Clone, Default, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
 pub struct Config {}
 
 impl Rule for Config {
     type Cache = NoCache;
 
+    const CROSS_FILE: bool = true;
+
     fn level(&self) -> LintLevel {
         LintLevel::Error
     }
@@ -28,7 +30,7 @@ impl Rule for Config {
     ) -> Result<Vec<(LintItem, String, LintResult)>> {
         let mut results = vec![];
 
-        for name in spec.sources.as_ref().unwrap_or(&HashMap::new()).keys() {
+        for name in spec.sources.as_ref().unwrap_or(&IndexMap::new()).keys() {
             if cache.common.sources.iter().filter(|s| *s == name).count() > 1 {
                 results.push((
                     LintItem::Source,
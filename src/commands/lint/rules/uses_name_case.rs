@@ -1,13 +1,13 @@
-use std::{
-    collections::HashMap,
-    fmt::{Display as FmtDisplay, Formatter, Result as FmtResult},
-};
+use std::fmt::{Display as FmtDisplay, Formatter, Result as FmtResult};
 
+use clap::ValueEnum;
 use heck::{
     ToKebabCase, ToLowerCamelCase, ToPascalCase, ToShoutyKebabCase, ToShoutySnakeCase, ToSnakeCase,
     ToTitleCase, ToTrainCase,
 };
-use serde::Deserialize;
+use indexmap::IndexMap;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     error::Result,
@@ -19,8 +19,9 @@ use crate::{
 };
 
 #[allow(clippy::enum_variant_names)]
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, ValueEnum)]
 #[serde(rename_all = "snake_case")]
+#[clap(rename_all = "snake_case")]
 pub enum Case {
     TitleCase,
     LowerCase,
@@ -56,7 +57,7 @@ impl FmtDisplay for Case {
 }
 
 impl Case {
-    fn convert(&self, name: &str) -> String {
+    pub(crate) fn convert(&self, name: &str) -> String {
         match self {
             Case::LowerCase => name.to_title_case().to_lowercase(),
             Case::UpperCase => name.to_title_case().to_uppercase(),
@@ -72,9 +73,11 @@ impl Case {
     }
 }
 
-#[derive(Debug, Clone, Default, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
 pub struct Config {
+    /// Case of metric names
     pub metric: Option<Case>,
+    /// Case of pageview names
     pub pageview: Option<Case>,
     // TODO: property
 }
@@ -96,7 +99,7 @@ impl Rule for Config {
         let metric_case = self.metric.as_ref().unwrap_or(&Case::TitleCase);
         let pageview_case = self.pageview.as_ref().unwrap_or(&Case::TitleCase);
 
-        for name in spec.metrics.as_ref().unwrap_or(&HashMap::new()).keys() {
+        for name in spec.metrics.as_ref().unwrap_or(&IndexMap::new()).keys() {
             if &metric_case.convert(name) != name {
                 results.push((
                     LintItem::Metric,
@@ -108,7 +111,7 @@ impl Rule for Config {
             }
         }
 
-        for name in spec.pageviews.as_ref().unwrap_or(&HashMap::new()).keys() {
+        for name in spec.pageviews.as_ref().unwrap_or(&IndexMap::new()).keys() {
             if &pageview_case.convert(name) != name {
                 results.push((
                     LintItem::Pageview,
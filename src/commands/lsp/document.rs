@@ -0,0 +1,391 @@
+use lsp_types::{Position, Range};
+
+/// Sections of a plan file whose entries are named items
+pub(super) const ITEM_SECTIONS: [&str; 3] = ["metrics", "pageviews", "sources"];
+
+/// Key of a mapping along with the keys of its parents
+#[derive(Debug)]
+pub(super) struct Key {
+    pub(super) path: Vec<String>,
+    pub(super) range: Range,
+}
+
+/// Source name referenced in the `sources` list of a metric or pageview
+#[derive(Debug)]
+pub(super) struct Reference {
+    pub(super) name: String,
+    pub(super) range: Range,
+}
+
+#[derive(Debug, PartialEq)]
+pub(super) enum Symbol {
+    /// Name of a metric, pageview or source where it is declared
+    Item { section: String, name: String },
+    /// Name of a source in a `sources` list
+    SourceRef(String),
+    /// Name of a rule in a `lint` block
+    Rule(String),
+}
+
+#[derive(Debug, PartialEq)]
+pub(super) enum Completion {
+    Sources,
+    Rules,
+}
+
+#[derive(Debug, Default)]
+struct Line {
+    parent: Vec<String>,
+    key: Option<(String, Range)>,
+    colon: Option<usize>,
+    refs: Vec<Reference>,
+}
+
+/// Line based view over the text of a plan file.
+///
+/// This does not parse the file, which allows it to work on the broken
+/// content an editor sends while the user is typing. Nesting is inferred
+/// from indentation, which holds for both block YAML and pretty printed JSON.
+#[derive(Debug)]
+pub(super) struct Document {
+    lines: Vec<Line>,
+}
+
+impl Document {
+    pub(super) fn new(text: &str) -> Self {
+        let mut stack: Vec<(usize, String)> = vec![];
+        let mut lines = vec![];
+
+        for (number, text) in text.lines().enumerate() {
+            let number = number as u32;
+            let content = strip_comment(text);
+            let trimmed = content.trim_start();
+            let indent = content.len() - trimmed.len();
+
+            if trimmed.trim().is_empty() {
+                // Blank lines belong to whatever is open at the cursor column
+                let parent = stack
+                    .iter()
+                    .filter(|(i, _)| *i < text.len())
+                    .map(|(_, k)| k.clone())
+                    .collect();
+
+                lines.push(Line {
+                    parent,
+                    ..Default::default()
+                });
+                continue;
+            }
+
+            let key = parse_key(trimmed);
+
+            // List items may be at the same indentation as their key
+            while stack.last().is_some_and(|(i, _)| {
+                *i > indent || (*i == indent && (key.is_some() || !is_list_item(trimmed)))
+            }) {
+                stack.pop();
+            }
+
+            let parent: Vec<String> = stack.iter().map(|(_, k)| k.clone()).collect();
+            let mut line = Line {
+                parent,
+                ..Default::default()
+            };
+
+            if let Some((name, start, end, colon)) = key {
+                line.key = Some((
+                    name.clone(),
+                    range(number, text, indent + start, indent + end),
+                ));
+                line.colon = Some(indent + colon);
+
+                if name == "sources" && is_item(&line.parent) {
+                    line.refs = references(number, text, indent + colon + 1);
+                }
+
+                stack.push((indent, name));
+            } else if line.parent.len() == 3
+                && line.parent[2] == "sources"
+                && is_item(&line.parent[..2])
+            {
+                let start = if is_list_item(trimmed) {
+                    indent + 1
+                } else {
+                    indent
+                };
+
+                line.refs = references(number, text, start);
+            }
+
+            lines.push(line);
+        }
+
+        Self { lines }
+    }
+
+    /// All keys in the document with their full paths
+    pub(super) fn keys(&self) -> impl Iterator<Item = Key> + '_ {
+        self.lines.iter().filter_map(|line| {
+            line.key.as_ref().map(|(name, range)| Key {
+                path: line.parent.iter().cloned().chain([name.clone()]).collect(),
+                range: *range,
+            })
+        })
+    }
+
+    /// All source references in the document
+    pub(super) fn references(&self) -> impl Iterator<Item = &Reference> {
+        self.lines.iter().flat_map(|line| &line.refs)
+    }
+
+    /// Find the range of the key with the given path
+    pub(super) fn find(&self, path: &[&str]) -> Option<Range> {
+        self.keys()
+            .find(|key| key.path.iter().map(String::as_str).eq(path.iter().copied()))
+            .map(|key| key.range)
+    }
+
+    pub(super) fn symbol_at(&self, position: Position) -> Option<Symbol> {
+        let line = self.lines.get(position.line as usize)?;
+
+        if let Some(reference) = line.refs.iter().find(|r| contains(&r.range, position)) {
+            return Some(Symbol::SourceRef(reference.name.clone()));
+        }
+
+        let (name, range) = line.key.as_ref()?;
+
+        if !contains(range, position) {
+            return None;
+        }
+
+        match line.parent.as_slice() {
+            [section] if section == "lint" => Some(Symbol::Rule(name.clone())),
+            [section] if ITEM_SECTIONS.contains(&section.as_str()) => Some(Symbol::Item {
+                section: section.clone(),
+                name: name.clone(),
+            }),
+            _ => None,
+        }
+    }
+
+    pub(super) fn completion_at(&self, position: Position) -> Option<Completion> {
+        let line = self.lines.get(position.line as usize)?;
+
+        let before_colon = line
+            .colon
+            .is_none_or(|colon| (position.character as usize) <= colon);
+
+        match (line.parent.as_slice(), &line.key) {
+            ([section], _) if section == "lint" && before_colon => Some(Completion::Rules),
+            (parent, Some((key, _))) if key == "sources" && is_item(parent) && !before_colon => {
+                Some(Completion::Sources)
+            }
+            ([section, name, key], None)
+                if key == "sources" && is_item(&[section.clone(), name.clone()]) =>
+            {
+                Some(Completion::Sources)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Whether the path points to a metric or pageview
+fn is_item(path: &[String]) -> bool {
+    path.len() == 2 && matches!(path[0].as_str(), "metrics" | "pageviews")
+}
+
+pub(crate) fn is_list_item(trimmed: &str) -> bool {
+    trimmed == "-" || trimmed.starts_with("- ")
+}
+
+pub(crate) fn strip_comment(text: &str) -> &str {
+    let mut quote = None;
+
+    for (i, c) in text.char_indices() {
+        match c {
+            '"' | '\'' if quote == Some(c) => quote = None,
+            '"' | '\'' if quote.is_none() => quote = Some(c),
+            '#' if quote.is_none() && (i == 0 || text[..i].ends_with(char::is_whitespace)) => {
+                return &text[..i];
+            }
+            _ => {}
+        }
+    }
+
+    text
+}
+
+/// Parse the key at the start of a line returning it with the offsets of its
+/// start, end and the colon following it
+pub(crate) fn parse_key(trimmed: &str) -> Option<(String, usize, usize, usize)> {
+    if is_list_item(trimmed) {
+        return None;
+    }
+
+    if let Some(quote @ ('"' | '\'')) = trimmed.chars().next() {
+        let end = trimmed[1..].find(quote)? + 1;
+        let colon = end + 1 + trimmed[end + 1..].find(':')?;
+
+        if !trimmed[end + 1..colon].trim().is_empty() {
+            return None;
+        }
+
+        return Some((trimmed[1..end].to_string(), 1, end, colon));
+    }
+
+    let colon = trimmed
+        .char_indices()
+        .find(|(i, c)| {
+            *c == ':'
+                && trimmed[i + 1..]
+                    .chars()
+                    .next()
+                    .is_none_or(char::is_whitespace)
+        })
+        .map(|(i, _)| i)?;
+
+    let key = trimmed[..colon].trim_end();
+
+    Some((key.to_string(), 0, key.len(), colon))
+}
+
+/// Collect the names in an inline list or list item starting at the offset
+fn references(number: u32, text: &str, offset: usize) -> Vec<Reference> {
+    let content = strip_comment(text);
+    let mut refs = vec![];
+    let mut start = offset;
+
+    for (i, c) in content[offset..]
+        .char_indices()
+        .map(|(i, c)| (i + offset, c))
+    {
+        if matches!(c, '[' | ']' | ',') {
+            refs.extend(reference(number, text, start, i));
+            start = i + 1;
+        }
+    }
+
+    refs.extend(reference(number, text, start, content.len()));
+    refs
+}
+
+fn reference(number: u32, text: &str, start: usize, end: usize) -> Option<Reference> {
+    let token = &text[start..end];
+    let leading = token.len() - token.trim_start().len();
+    let token = token.trim();
+    let token = token
+        .strip_prefix(['"', '\''])
+        .and_then(|t| t.strip_suffix(['"', '\'']))
+        .map_or((token, 0), |t| (t, 1));
+
+    if token.0.is_empty() {
+        return None;
+    }
+
+    let start = start + leading + token.1;
+
+    Some(Reference {
+        name: token.0.to_string(),
+        range: range(number, text, start, start + token.0.len()),
+    })
+}
+
+fn range(number: u32, text: &str, start: usize, end: usize) -> Range {
+    Range::new(
+        Position::new(number, utf16_len(&text[..start])),
+        Position::new(number, utf16_len(&text[..end])),
+    )
+}
+
+fn utf16_len(text: &str) -> u32 {
+    text.encode_utf16().count() as u32
+}
+
+fn contains(range: &Range, position: Position) -> bool {
+    range.start <= position && position <= range.end
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const PLAN: &str = r#"lint:
+  uses_name_case:
+    metric: snake_case
+metrics:
+  User Logged In: # comment
+    description: User logs into the application
+    sources: [ios, "web"]
+pageviews:
+  Home Page:
+    sources:
+      - android
+sources:
+  ios:
+    description: iOS application
+"#;
+
+    #[test]
+    fn keys() {
+        let document = Document::new(PLAN);
+
+        assert_eq!(
+            document.find(&["metrics", "User Logged In"]),
+            Some(Range::new(Position::new(4, 2), Position::new(4, 16)))
+        );
+        assert_eq!(
+            document.find(&["sources", "ios"]),
+            Some(Range::new(Position::new(12, 2), Position::new(12, 5)))
+        );
+        assert_eq!(document.find(&["metrics", "Home Page"]), None);
+    }
+
+    #[test]
+    fn references() {
+        let document = Document::new(PLAN);
+        let refs = document
+            .references()
+            .map(|r| (r.name.as_str(), r.range.start.character))
+            .collect::<Vec<_>>();
+
+        assert_eq!(refs, vec![("ios", 14), ("web", 20), ("android", 8)]);
+    }
+
+    #[test]
+    fn symbols() {
+        let document = Document::new(PLAN);
+
+        assert_eq!(
+            document.symbol_at(Position::new(1, 4)),
+            Some(Symbol::Rule("uses_name_case".to_string()))
+        );
+        assert_eq!(
+            document.symbol_at(Position::new(6, 15)),
+            Some(Symbol::SourceRef("ios".to_string()))
+        );
+        assert_eq!(
+            document.symbol_at(Position::new(8, 3)),
+            Some(Symbol::Item {
+                section: "pageviews".to_string(),
+                name: "Home Page".to_string()
+            })
+        );
+        assert_eq!(document.symbol_at(Position::new(5, 6)), None);
+    }
+
+    #[test]
+    fn completions() {
+        let document = Document::new("lint:\n  needs_\nmetrics:\n  A:\n    sources: [\n");
+
+        assert_eq!(
+            document.completion_at(Position::new(1, 8)),
+            Some(Completion::Rules)
+        );
+        assert_eq!(
+            document.completion_at(Position::new(4, 14)),
+            Some(Completion::Sources)
+        );
+        assert_eq!(document.completion_at(Position::new(3, 2)), None);
+    }
+}
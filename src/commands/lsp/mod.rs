@@ -0,0 +1,597 @@
+use std::{
+    collections::{HashMap, HashSet},
+    env::current_dir,
+    fs::read_to_string,
+    path::PathBuf,
+};
+
+use clap::Parser;
+use eyre::eyre;
+use indexmap::IndexMap;
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, RequestId, Response};
+use lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionOptions, CompletionParams, Diagnostic,
+    DiagnosticSeverity, DidChangeTextDocumentParams, DidChangeWatchedFilesRegistrationOptions,
+    DidCloseTextDocumentParams, DidOpenTextDocumentParams, FileSystemWatcher, GlobPattern,
+    GotoDefinitionParams, Hover, HoverContents, HoverParams, HoverProviderCapability,
+    InitializeParams, Location, MarkupContent, MarkupKind, OneOf, Position,
+    PublishDiagnosticsParams, Range, Registration, RegistrationParams, RenameParams,
+    ServerCapabilities, TextDocumentSyncCapability, TextDocumentSyncKind, TextEdit, Url,
+    WorkspaceEdit,
+    notification::{
+        DidChangeTextDocument, DidChangeWatchedFiles, DidCloseTextDocument, DidOpenTextDocument,
+        DidSaveTextDocument, Notification as _, PublishDiagnostics,
+    },
+    request::{
+        Completion as CompletionRequest, GotoDefinition, HoverRequest, RegisterCapability, Rename,
+        Request as _,
+    },
+};
+use schemars::schema_for;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, from_value, to_value};
+use tracing::{debug, instrument, trace};
+
+use crate::{
+    commands::lint::{LintItem, LintLevel, check, rules::RulesConfig},
+    config::Config,
+    error::Result,
+    schema::{SchemaOpt, spec::Spec, strict::fields},
+};
+
+use self::document::{Completion, Document, Symbol};
+
+pub(crate) mod document;
+
+/// Files the client is asked to report changes to
+const WATCHED_FILES: &str = "**/*.{yaml,yml,json}";
+
+/// Run the language server over stdio
+#[derive(Debug, Parser)]
+pub struct Lsp {
+    /// Data plan file or folder (defaults to the workspace root)
+    pub plan: Option<PathBuf>,
+
+    /// Plan options from `ods.toml`
+    #[clap(skip)]
+    pub(crate) schema: SchemaOpt,
+
+    /// Rule configuration from `ods.toml` overriding the lints file
+    #[clap(skip)]
+    pub(crate) rules: Option<RulesConfig>,
+}
+
+impl Lsp {
+    pub(crate) fn apply(&mut self, config: &Config) {
+        self.schema.apply(config);
+        self.rules = config.lint.rules.clone();
+    }
+
+    #[instrument(name = "lsp", skip_all)]
+    pub(crate) fn run(&self) -> Result {
+        let (connection, io_threads) = Connection::stdio();
+
+        let (id, params) = connection.initialize_start()?;
+        let params: InitializeParams = from_value(params)?;
+
+        let watch_files = params
+            .capabilities
+            .workspace
+            .as_ref()
+            .and_then(|w| w.did_change_watched_files.as_ref())
+            .and_then(|c| c.dynamic_registration)
+            .unwrap_or_default();
+
+        #[allow(deprecated)]
+        let plan = match (
+            self.plan.as_ref().or(self.schema.plan.as_ref()),
+            params.root_uri,
+        ) {
+            (Some(plan), _) => plan.clone(),
+            (None, Some(uri)) => uri
+                .to_file_path()
+                .map_err(|_| eyre!("unable to use workspace root {uri}"))?,
+            (None, None) => current_dir()?,
+        };
+
+        connection.initialize_finish(
+            id,
+            serde_json::json!({
+                "capabilities": capabilities(),
+                "serverInfo": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+            }),
+        )?;
+
+        // Clients only send the changes made outside of the editor to the
+        // files they were asked to watch
+        if watch_files {
+            let registration = Registration {
+                id: DidChangeWatchedFiles::METHOD.to_string(),
+                method: DidChangeWatchedFiles::METHOD.to_string(),
+                register_options: Some(to_value(DidChangeWatchedFilesRegistrationOptions {
+                    watchers: vec![FileSystemWatcher {
+                        glob_pattern: GlobPattern::String(WATCHED_FILES.to_string()),
+                        kind: None,
+                    }],
+                })?),
+            };
+
+            connection.sender.send(Message::Request(Request::new(
+                RequestId::from(RegisterCapability::METHOD.to_string()),
+                RegisterCapability::METHOD.to_string(),
+                RegistrationParams {
+                    registrations: vec![registration],
+                },
+            )))?;
+        }
+
+        let mut server = Server {
+            schema: SchemaOpt {
+                plan: Some(plan),
+                ..self.schema.clone()
+            },
+            rules: self.rules.clone(),
+            documents: HashMap::new(),
+            published: HashSet::new(),
+        };
+
+        server.publish_diagnostics(&connection)?;
+
+        for message in &connection.receiver {
+            match message {
+                Message::Request(request) => {
+                    if connection.handle_shutdown(&request)? {
+                        break;
+                    }
+
+                    let response = server.handle_request(request);
+                    connection.sender.send(Message::Response(response))?;
+                }
+                Message::Notification(notification) => {
+                    if server.handle_notification(notification)? {
+                        server.publish_diagnostics(&connection)?;
+                    }
+                }
+                Message::Response(_) => {}
+            }
+        }
+
+        // The writer thread only stops once the connection is dropped
+        drop(connection);
+        io_threads.join()?;
+
+        Ok(())
+    }
+}
+
+fn capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        definition_provider: Some(OneOf::Left(true)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec!["[".into(), ",".into(), " ".into()]),
+            ..Default::default()
+        }),
+        rename_provider: Some(OneOf::Left(true)),
+        ..Default::default()
+    }
+}
+
+/// File of the plan as currently seen by the editor
+struct File {
+    path: PathBuf,
+    document: Document,
+    spec: Result<Spec>,
+}
+
+struct Server {
+    schema: SchemaOpt,
+    rules: Option<RulesConfig>,
+    /// Content of the files open in the editor keyed by their path
+    documents: HashMap<PathBuf, String>,
+    published: HashSet<Url>,
+}
+
+impl Server {
+    /// Load the plan, preferring the editor content over what is on disk
+    fn load(&self) -> Result<IndexMap<String, File>> {
+        let mut files = IndexMap::new();
+
+        for (name, path) in self.schema.files()? {
+            let text = match self.documents.get(&path) {
+                Some(text) => text.clone(),
+                None => read_to_string(&path)?,
+            };
+
+            trace!("Loading file: {}", name);
+            files.insert(
+                name,
+                File {
+                    spec: Spec::parse(&path, &text),
+                    document: Document::new(&text),
+                    path,
+                },
+            );
+        }
+
+        Ok(files)
+    }
+
+    fn handle_notification(&mut self, notification: Notification) -> Result<bool> {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: DidOpenTextDocumentParams = from_value(notification.params)?;
+
+                if let Ok(path) = params.text_document.uri.to_file_path() {
+                    self.documents.insert(path, params.text_document.text);
+                }
+            }
+            DidChangeTextDocument::METHOD => {
+                let params: DidChangeTextDocumentParams = from_value(notification.params)?;
+
+                if let (Ok(path), Some(change)) = (
+                    params.text_document.uri.to_file_path(),
+                    params.content_changes.into_iter().last(),
+                ) {
+                    self.documents.insert(path, change.text);
+                }
+            }
+            DidCloseTextDocument::METHOD => {
+                let params: DidCloseTextDocumentParams = from_value(notification.params)?;
+
+                if let Ok(path) = params.text_document.uri.to_file_path() {
+                    self.documents.remove(&path);
+                }
+            }
+            DidSaveTextDocument::METHOD | DidChangeWatchedFiles::METHOD => {}
+            _ => return Ok(false),
+        }
+
+        Ok(true)
+    }
+
+    fn handle_request(&self, request: Request) -> Response {
+        debug!("Handling request: {}", request.method);
+
+        let id = request.id.clone();
+
+        let result = match request.method.as_str() {
+            GotoDefinition::METHOD => self.respond(request, Self::definition),
+            CompletionRequest::METHOD => self.respond(request, Self::completion),
+            HoverRequest::METHOD => self.respond(request, Self::hover),
+            Rename::METHOD => self.respond(request, Self::rename),
+            _ => {
+                return Response::new_err(
+                    id,
+                    ErrorCode::MethodNotFound as i32,
+                    format!("unsupported request {}", request.method),
+                );
+            }
+        };
+
+        match result {
+            Ok(value) => Response::new_ok(id, value),
+            Err(e) => Response::new_err(id, ErrorCode::RequestFailed as i32, e.to_string()),
+        }
+    }
+
+    fn respond<P: DeserializeOwned, R: serde::Serialize>(
+        &self,
+        request: Request,
+        handler: fn(&Self, P) -> Result<R>,
+    ) -> Result<Value> {
+        Ok(to_value(handler(self, from_value(request.params)?)?)?)
+    }
+
+    /// Find the file and symbol at the given position
+    fn symbol_at(
+        &self,
+        files: &IndexMap<String, File>,
+        uri: &Url,
+        position: Position,
+    ) -> Option<Symbol> {
+        let path = uri.to_file_path().ok()?;
+
+        files
+            .values()
+            .find(|file| file.path == path)?
+            .document
+            .symbol_at(position)
+    }
+
+    fn definition(&self, params: GotoDefinitionParams) -> Result<Option<Vec<Location>>> {
+        let position = params.text_document_position_params;
+        let files = self.load()?;
+
+        let (section, name) =
+            match self.symbol_at(&files, &position.text_document.uri, position.position) {
+                Some(Symbol::SourceRef(name)) => ("sources".to_string(), name),
+                Some(Symbol::Item { section, name }) => (section, name),
+                _ => return Ok(None),
+            };
+
+        Ok(Some(declarations(&files, &section, &name).collect()))
+    }
+
+    fn completion(&self, params: CompletionParams) -> Result<Option<Vec<CompletionItem>>> {
+        let position = params.text_document_position;
+        let files = self.load()?;
+
+        let Ok(path) = position.text_document.uri.to_file_path() else {
+            return Ok(None);
+        };
+
+        let completion = files
+            .values()
+            .find(|file| file.path == path)
+            .and_then(|file| file.document.completion_at(position.position));
+
+        let items = match completion {
+            Some(Completion::Sources) => {
+                let mut sources = IndexMap::new();
+
+                for file in files.values() {
+                    if let Ok(Spec {
+                        sources: Some(specs),
+                        ..
+                    }) = &file.spec
+                    {
+                        for (name, source) in specs {
+                            sources
+                                .entry(name.clone())
+                                .or_insert_with(|| source.description.clone());
+                        }
+                    }
+                }
+
+                sources
+                    .into_iter()
+                    .map(|(name, description)| CompletionItem {
+                        label: name,
+                        kind: Some(CompletionItemKind::ENUM_MEMBER),
+                        detail: description,
+                        ..Default::default()
+                    })
+                    .collect()
+            }
+            Some(Completion::Rules) => fields::<RulesConfig>()
+                .iter()
+                .map(|rule| CompletionItem {
+                    label: rule.to_string(),
+                    kind: Some(CompletionItemKind::PROPERTY),
+                    detail: rule_description(rule),
+                    ..Default::default()
+                })
+                .collect(),
+            None => return Ok(None),
+        };
+
+        Ok(Some(items))
+    }
+
+    fn hover(&self, params: HoverParams) -> Result<Option<Hover>> {
+        let position = params.text_document_position_params;
+        let files = self.load()?;
+
+        let description =
+            match self.symbol_at(&files, &position.text_document.uri, position.position) {
+                Some(Symbol::Rule(rule)) => rule_description(&rule),
+                Some(Symbol::SourceRef(name)) => item_description(&files, "sources", &name),
+                Some(Symbol::Item { section, name }) => item_description(&files, &section, &name),
+                None => None,
+            };
+
+        Ok(description.map(|value| Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value,
+            }),
+            range: None,
+        }))
+    }
+
+    fn rename(&self, params: RenameParams) -> Result<Option<WorkspaceEdit>> {
+        let position = params.text_document_position;
+        let files = self.load()?;
+
+        let (section, name) =
+            match self.symbol_at(&files, &position.text_document.uri, position.position) {
+                Some(Symbol::SourceRef(name)) => ("sources".to_string(), name),
+                Some(Symbol::Item { section, name }) => (section, name),
+                _ => return Ok(None),
+            };
+
+        let mut changes: HashMap<Url, Vec<TextEdit>> = HashMap::new();
+
+        for file in files.values() {
+            let Ok(uri) = Url::from_file_path(&file.path) else {
+                continue;
+            };
+
+            let mut ranges = file
+                .document
+                .keys()
+                .filter(|key| key.path == [section.as_str(), name.as_str()])
+                .map(|key| key.range)
+                .collect::<Vec<_>>();
+
+            if section == "sources" {
+                ranges.extend(
+                    file.document
+                        .references()
+                        .filter(|reference| reference.name == name)
+                        .map(|reference| reference.range),
+                );
+            }
+
+            if !ranges.is_empty() {
+                changes.entry(uri).or_default().extend(
+                    ranges
+                        .into_iter()
+                        .map(|range| TextEdit::new(range, params.new_name.clone())),
+                );
+            }
+        }
+
+        Ok(Some(WorkspaceEdit {
+            changes: Some(changes),
+            ..Default::default()
+        }))
+    }
+
+    fn publish_diagnostics(&mut self, connection: &Connection) -> Result {
+        let files = match self.load() {
+            Ok(files) => files,
+            Err(e) => {
+                debug!("Unable to load plan: {e}");
+                return Ok(());
+            }
+        };
+
+        let mut diagnostics: IndexMap<Url, Vec<Diagnostic>> = IndexMap::new();
+        let mut specs = IndexMap::new();
+        let mut documents = IndexMap::new();
+
+        for (name, file) in files {
+            let Ok(uri) = Url::from_file_path(&file.path) else {
+                continue;
+            };
+
+            let entry = diagnostics.entry(uri.clone()).or_default();
+
+            match file.spec {
+                Ok(spec) => {
+                    specs.insert(name.clone(), spec);
+                }
+                Err(e) => entry.push(Diagnostic {
+                    range: error_range(&e),
+                    severity: Some(DiagnosticSeverity::ERROR),
+                    source: Some(env!("CARGO_PKG_NAME").to_string()),
+                    message: e.to_string(),
+                    ..Default::default()
+                }),
+            }
+
+            documents.insert(name, (uri, file.document));
+        }
+
+        let strict = self.schema.is_strict(&specs);
+
+        for (name, spec_results) in check(&specs, None, strict, self.rules.as_ref(), None)? {
+            let (uri, document) = &documents[&name];
+
+            for (ty, ty_results) in spec_results {
+                for (item, results) in ty_results {
+                    let range = match ty {
+                        LintItem::Metric => document.find(&["metrics", &item]),
+                        LintItem::Pageview => document.find(&["pageviews", &item]),
+                        LintItem::Source => document.find(&["sources", &item]),
+                        LintItem::Key => document
+                            .keys()
+                            .find(|key| key.path.join(".") == item)
+                            .map(|key| key.range),
+                    }
+                    .unwrap_or_default();
+
+                    for (level, result) in results {
+                        let severity = match level {
+                            LintLevel::Off => continue,
+                            LintLevel::Warning => DiagnosticSeverity::WARNING,
+                            LintLevel::Error => DiagnosticSeverity::ERROR,
+                        };
+
+                        diagnostics
+                            .entry(uri.clone())
+                            .or_default()
+                            .push(Diagnostic {
+                                range,
+                                severity: Some(severity),
+                                source: Some(env!("CARGO_PKG_NAME").to_string()),
+                                message: result.message,
+                                ..Default::default()
+                            });
+                    }
+                }
+            }
+        }
+
+        // Clear diagnostics of files which are no longer part of the plan
+        for uri in self.published.drain() {
+            diagnostics.entry(uri).or_default();
+        }
+
+        for (uri, diagnostics) in diagnostics {
+            self.published.insert(uri.clone());
+
+            connection
+                .sender
+                .send(Message::Notification(Notification::new(
+                    PublishDiagnostics::METHOD.to_string(),
+                    PublishDiagnosticsParams::new(uri, diagnostics, None),
+                )))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Locations where the item with the given name is declared
+fn declarations<'a>(
+    files: &'a IndexMap<String, File>,
+    section: &'a str,
+    name: &'a str,
+) -> impl Iterator<Item = Location> + 'a {
+    files.values().filter_map(move |file| {
+        let range = file.document.find(&[section, name])?;
+        let uri = Url::from_file_path(&file.path).ok()?;
+
+        Some(Location::new(uri, range))
+    })
+}
+
+fn item_description(files: &IndexMap<String, File>, section: &str, name: &str) -> Option<String> {
+    files.values().find_map(|file| {
+        let spec = file.spec.as_ref().ok()?;
+
+        match section {
+            "metrics" => spec.metrics.as_ref()?.get(name)?.description.clone(),
+            "pageviews" => spec.pageviews.as_ref()?.get(name)?.description.clone(),
+            "sources" => spec.sources.as_ref()?.get(name)?.description.clone(),
+            _ => None,
+        }
+    })
+}
+
+fn rule_description(rule: &str) -> Option<String> {
+    let schema = schema_for!(RulesConfig);
+
+    schema
+        .as_value()
+        .pointer(&format!("/properties/{rule}/description"))
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+fn error_range(e: &eyre::Report) -> Range {
+    let location = if let Some(e) = e.downcast_ref::<serde_yml::Error>() {
+        e.location().map(|l| (l.line(), l.column()))
+    } else {
+        e.downcast_ref::<serde_json::Error>()
+            .map(|e| (e.line(), e.column()))
+    };
+
+    location
+        .map(|(line, column)| {
+            let position = Position::new(
+                line.saturating_sub(1) as u32,
+                column.saturating_sub(1) as u32,
+            );
+
+            Range::new(position, position)
+        })
+        .unwrap_or_default()
+}
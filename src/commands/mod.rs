@@ -1,22 +1,61 @@
 use clap::Parser;
 
-use crate::error::Result;
+use crate::{config::Config, error::Result};
 
+pub mod diff;
+pub mod fmt;
 pub mod generate;
+pub mod import;
+pub mod init;
 pub mod lint;
+pub mod lsp;
+pub mod schema;
+pub mod serve;
 
 #[derive(Debug, Parser)]
 pub enum Subcommands {
+    Diff(diff::Diff),
+    Fmt(fmt::Fmt),
     #[clap(aliases = &["gen", "g"])]
     Generate(generate::Generate),
+    #[clap(subcommand)]
+    Import(import::Import),
+    Init(init::Init),
     Lint(lint::Lint),
+    Lsp(lsp::Lsp),
+    Schema(schema::Schema),
+    Serve(serve::Serve),
 }
 
 impl Subcommands {
+    /// Whether the command reads `ods.toml`, which the others don't even parse
+    pub(crate) fn uses_config(&self) -> bool {
+        !matches!(self, Self::Import(_) | Self::Init(_) | Self::Schema(_))
+    }
+
+    pub(crate) fn apply(&mut self, config: &Config) {
+        match self {
+            Self::Diff(x) => x.apply(config),
+            Self::Fmt(x) => x.apply(config),
+            Self::Generate(x) => x.apply(config),
+            Self::Lint(x) => x.apply(config),
+            Self::Lsp(x) => x.apply(config),
+            Self::Serve(x) => x.apply(config),
+            Self::Import(_) | Self::Init(_) | Self::Schema(_) => {}
+        }
+    }
+
     pub(crate) fn run(&self) -> Result {
         match self {
+            Self::Diff(x) => x.run(),
+            Self::Fmt(x) => x.run(),
             Self::Generate(x) => x.run(),
+            Self::Import(x) => x.run(),
+            Self::Init(x) => x.run(),
             Self::Lint(x) => x.run(),
+            Self::Lsp(x) => x.run(),
+            Self::Schema(x) => x.run(),
+            Self::Serve(x) => x.run(),
         }
     }
 }
@@ -0,0 +1,31 @@
+use std::{fs::write, path::PathBuf};
+
+use anstream::println;
+use clap::Parser;
+use schemars::schema_for;
+use serde_json::to_string_pretty;
+use tracing::instrument;
+
+use crate::{error::Result, schema::spec::Spec};
+
+/// Print JSON Schema of plan files
+#[derive(Debug, Parser)]
+pub struct Schema {
+    /// Write the schema to a file instead of stdout
+    #[clap(short)]
+    pub output: Option<PathBuf>,
+}
+
+impl Schema {
+    #[instrument(name = "schema", skip_all)]
+    pub(crate) fn run(&self) -> Result {
+        let schema = to_string_pretty(&schema_for!(Spec))?;
+
+        match &self.output {
+            Some(output) => write(output, format!("{schema}\n"))?,
+            None => println!("{schema}"),
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,176 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+    thread::spawn,
+};
+
+use anstream::println;
+use clap::Parser;
+use eyre::eyre;
+use owo_colors::OwoColorize;
+use serde_json::to_string_pretty;
+use tiny_http::{Header, Request, Response, Server};
+use tracing::{debug, instrument, trace};
+
+use crate::{
+    commands::generate::{
+        catalog::{Catalog, EventKind, slug},
+        html,
+    },
+    config::Config,
+    error::Result,
+    schema::SchemaOpt,
+};
+
+/// Response body along with its content type
+type Page = (&'static str, String);
+
+/// Serve the plan explorer and a JSON API, rebuilding them on changes
+#[derive(Debug, Parser)]
+pub struct Serve {
+    #[clap(flatten)]
+    pub schema: SchemaOpt,
+
+    /// Port to listen on
+    #[clap(short, long, default_value_t = 8080)]
+    pub port: u16,
+
+    /// Address to listen on
+    #[clap(long, default_value = "127.0.0.1")]
+    pub host: String,
+}
+
+impl Serve {
+    pub(crate) fn apply(&mut self, config: &Config) {
+        self.schema.apply(config);
+    }
+
+    #[instrument(name = "serve", skip_all)]
+    pub(crate) fn run(&self) -> Result {
+        let server = Server::http((self.host.as_str(), self.port))
+            .map_err(|e| eyre!("unable to listen on {}:{}: {e}", self.host, self.port))?;
+
+        let pages = Arc::new(RwLock::new(HashMap::new()));
+
+        let handler = pages.clone();
+        spawn(move || {
+            for request in server.incoming_requests() {
+                respond(&handler, request);
+            }
+        });
+
+        let url = format!("http://{}:{}", self.host, self.port);
+
+        // Keep serving the last working version while the plan is invalid
+        self.schema.watch(None, || {
+            let built = self.build()?;
+            *pages.write().expect("Failed to lock the pages") = built;
+
+            println!("{} {}", "Serving at".green(), url.bold());
+            Ok(())
+        })
+    }
+
+    /// Render the explorer and the API responses from the plan
+    fn build(&self) -> Result<HashMap<String, Page>> {
+        let files = self.schema.load()?;
+
+        self.schema.check_strict(&files)?;
+
+        let catalog = Catalog::new(&files);
+        let mut pages = HashMap::new();
+
+        // Rendering rejects items sharing a slug, which keeps the API paths
+        // below from overwriting each other too
+        for (path, content) in html::render(&catalog)? {
+            pages.insert(format!("/{path}"), ("text/html; charset=utf-8", content));
+        }
+
+        let mut api = |path: String, body: String| {
+            pages.insert(path, ("application/json", body));
+        };
+
+        for (kind, endpoint) in [
+            (EventKind::Metric, "metrics"),
+            (EventKind::Pageview, "pageviews"),
+        ] {
+            let events = catalog
+                .events
+                .iter()
+                .filter(|e| e.kind == kind)
+                .collect::<Vec<_>>();
+
+            for event in &events {
+                let path = format!("/api/{endpoint}/{}", slug(event.name));
+                api(path, to_string_pretty(event)?);
+            }
+
+            api(format!("/api/{endpoint}"), to_string_pretty(&events)?);
+        }
+
+        for source in &catalog.sources {
+            let path = format!("/api/sources/{}", slug(source.name));
+            api(path, to_string_pretty(source)?);
+        }
+
+        api(
+            "/api/sources".to_string(),
+            to_string_pretty(&catalog.sources)?,
+        );
+        api(
+            "/api".to_string(),
+            to_string_pretty(&["/api/metrics", "/api/pageviews", "/api/sources"])?,
+        );
+
+        debug!("Built {} pages", pages.len());
+        Ok(pages)
+    }
+}
+
+fn respond(pages: &RwLock<HashMap<String, Page>>, request: Request) {
+    let path = decode(request.url().split(['?', '#']).next().unwrap_or_default());
+    let path = match path.trim_end_matches('/') {
+        "" => "/index.html",
+        path => path,
+    };
+
+    trace!("{} {}", request.method(), path);
+
+    let response = match pages.read().expect("Failed to lock the pages").get(path) {
+        Some((content_type, body)) => Response::from_string(body.clone()).with_header(
+            Header::from_bytes("Content-Type", *content_type).expect("Failed to build header"),
+        ),
+        None => Response::from_string("not found").with_status_code(404),
+    };
+
+    if let Err(e) = request.respond(response) {
+        debug!("Failed to respond: {e}");
+    }
+}
+
+/// Decode the percent-encoded bytes of a URL path, which browsers send for
+/// non-ASCII names
+fn decode(path: &str) -> String {
+    let mut bytes = Vec::with_capacity(path.len());
+    let mut rest = path.as_bytes();
+
+    while let Some((&byte, tail)) = rest.split_first() {
+        let hex = tail
+            .get(..2)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        match (byte, hex) {
+            (b'%', Some(decoded)) => {
+                bytes.push(decoded);
+                rest = &tail[2..];
+            }
+            _ => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&bytes).into_owned()
+}
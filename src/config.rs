@@ -0,0 +1,141 @@
+use std::{
+    env::current_dir,
+    fs::read_to_string,
+    path::{Path, PathBuf},
+};
+
+use eyre::eyre;
+use indexmap::IndexMap;
+use serde::Deserialize;
+use tracing::debug;
+
+use crate::{
+    commands::{
+        generate::{GenerateFormat, GenerateLang, dbt, iglu, markdown, sql},
+        lint::{LintFormat, rules::RulesConfig},
+    },
+    error::Result,
+};
+
+/// Name of the project configuration file
+pub const CONFIG_FILE: &str = "ods.toml";
+
+/// Project configuration, used as defaults for the command line flags
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Data plan file or folder
+    pub plan: Option<PathBuf>,
+
+    /// Reject unknown keys in the plan
+    pub strict: Option<bool>,
+
+    /// Only load files in the plan folder matching these globs
+    #[serde(default)]
+    pub include: Vec<String>,
+
+    /// Skip files in the plan folder matching these globs
+    #[serde(default)]
+    pub exclude: Vec<String>,
+
+    #[serde(default)]
+    pub fmt: FmtConfig,
+
+    #[serde(default)]
+    pub generate: GenerateConfig,
+
+    #[serde(default)]
+    pub lint: LintConfig,
+
+    /// Folder containing the configuration file
+    #[serde(skip)]
+    pub dir: PathBuf,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FmtConfig {
+    /// Sort metrics and pageviews by name
+    pub sort: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GenerateConfig {
+    /// Folder to write the generated code to
+    pub output: Option<PathBuf>,
+
+    /// Formats to generate when none are given on the command line
+    #[serde(default)]
+    pub formats: Vec<GenerateFormat>,
+
+    /// Languages to generate typed events for when none are given on the
+    /// command line
+    #[serde(default)]
+    pub langs: Vec<GenerateLang>,
+
+    /// Folder of MiniJinja templates to render into the output
+    pub template: Option<PathBuf>,
+
+    /// Plugins to generate with when none are given on the command line
+    #[serde(default)]
+    pub plugins: Vec<String>,
+
+    /// Parameters sent to the plugins
+    #[serde(default)]
+    pub plugin_opts: IndexMap<String, String>,
+
+    /// Package of the generated code
+    pub package: Option<String>,
+
+    #[serde(default)]
+    pub markdown: markdown::MarkdownOpt,
+
+    #[serde(default)]
+    pub iglu: iglu::IgluOpt,
+
+    #[serde(default)]
+    pub sql: sql::SqlOpt,
+
+    #[serde(default)]
+    pub dbt: dbt::DbtOpt,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LintConfig {
+    /// Format of the lint report
+    pub format: Option<LintFormat>,
+
+    /// Exit with a zero code even on lint errors
+    pub no_fail: Option<bool>,
+
+    /// Rule configuration overriding the lints file of the plan
+    pub rules: Option<RulesConfig>,
+}
+
+impl Config {
+    /// Find the configuration file by walking up from the current folder
+    pub fn find() -> Result<Option<Self>> {
+        let cwd = current_dir()?;
+
+        let Some(dir) = cwd.ancestors().find(|dir| dir.join(CONFIG_FILE).is_file()) else {
+            return Ok(None);
+        };
+
+        let path = dir.join(CONFIG_FILE);
+        debug!("Using config file: {}", path.display());
+
+        let mut config: Config = toml::from_str(&read_to_string(&path)?)
+            .map_err(|e| eyre!("unable to parse {}: {}", path.display(), e.message()))?;
+
+        config.dir = dir.to_path_buf();
+
+        Ok(Some(config))
+    }
+
+    /// Resolve a path in the config relative to the config file
+    pub fn resolve(&self, path: &Path) -> PathBuf {
+        self.dir.join(path)
+    }
+}
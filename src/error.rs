@@ -1,7 +1,7 @@
 use std::io::Write;
 
 use anstream::{eprintln, stderr, stdout};
-use eyre::Result as EyreResult;
+use eyre::{Report, Result as EyreResult};
 use owo_colors::OwoColorize;
 use proc_exit::Code;
 
@@ -13,7 +13,7 @@ pub fn finish(result: Result) {
         // in order to wrap all instances of it.
         let err = e;
 
-        eprintln!("{}: {err}", "error".red().bold());
+        print_error(&err);
         Code::FAILURE
     } else {
         Code::SUCCESS
@@ -22,6 +22,10 @@ pub fn finish(result: Result) {
     exit(code);
 }
 
+pub fn print_error(err: &Report) {
+    eprintln!("{}: {err}", "error".red().bold());
+}
+
 pub fn exit(code: Code) -> ! {
     stdout().flush().unwrap();
     stderr().flush().unwrap();
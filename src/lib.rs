@@ -2,8 +2,9 @@ use clap::Parser;
 use clap_verbosity_flag::{InfoLevel, Verbosity};
 use colorchoice_clap::Color;
 
-use crate::{commands::Subcommands, error::Result};
+use crate::{commands::Subcommands, config::Config, error::Result};
 
+pub mod config;
 pub mod error;
 mod styles;
 
@@ -27,7 +28,13 @@ pub struct App {
 }
 
 impl App {
-    pub fn run(self) -> Result {
+    pub fn run(mut self) -> Result {
+        // Without a config the defaults still settle how flags combine
+        if self.cmd.uses_config() {
+            let config = Config::find()?.unwrap_or_default();
+            self.cmd.apply(&config);
+        }
+
         self.cmd.run()
     }
 
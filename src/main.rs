@@ -1,4 +1,4 @@
-use std::io::stdout;
+use std::io::stderr;
 
 use anstream::{AutoStream, ColorChoice};
 use clap::Parser;
@@ -15,7 +15,8 @@ fn main() {
             tracing_subscriber::fmt::layer()
                 .without_time()
                 .with_target(false)
-                .with_ansi(!matches!(AutoStream::choice(&stdout()), ColorChoice::Never))
+                .with_writer(stderr)
+                .with_ansi(!matches!(AutoStream::choice(&stderr()), ColorChoice::Never))
                 .with_filter(program.verbose.tracing_level_filter()),
         )
         .init();
@@ -0,0 +1,226 @@
+use std::{
+    path::{Component, Path, PathBuf, absolute},
+    process::Command,
+};
+
+use eyre::eyre;
+use indexmap::IndexMap;
+use rayon::prelude::*;
+use tracing::{debug, instrument, trace};
+
+use crate::{
+    error::Result,
+    schema::{DEFAULT_OUTPUT, SchemaOpt, spec::Spec},
+};
+
+/// Plan file which differs from a git revision
+#[derive(Debug)]
+pub(crate) struct ChangedFile {
+    /// Path of the file in the working tree
+    pub(crate) path: PathBuf,
+    /// Content at the revision if the file existed then
+    pub(crate) old: Option<String>,
+}
+
+/// Location of the plan inside its git repository
+struct Repo {
+    root: PathBuf,
+    /// Path of the plan relative to the root, separated by `/` like git does
+    relative: String,
+    is_file: bool,
+}
+
+impl Repo {
+    fn pathspec(&self) -> &str {
+        if self.relative.is_empty() {
+            "."
+        } else {
+            &self.relative
+        }
+    }
+
+    fn git(&self, args: &[&str]) -> Result<String> {
+        git(&self.root, args)
+    }
+}
+
+impl SchemaOpt {
+    fn repo(&self) -> Result<Repo> {
+        let plan = self.plan()?.canonicalize()?;
+        let (root, relative) = repo_path(&plan)?;
+
+        Ok(Repo {
+            root,
+            relative,
+            is_file: plan.is_file(),
+        })
+    }
+
+    /// Map the paths git lists to the names of the plan files, dropping the
+    /// ones which are not part of the plan
+    fn plan_names<'a>(&self, repo: &Repo, listing: &'a str) -> Result<Vec<(String, &'a str)>> {
+        let is_selected = self.matcher()?;
+
+        // Output folder inside the plan, relative to it like the names
+        let exclude_dir = match &self.exclude_dir {
+            Some(dir) => absolute(dir)?
+                .strip_prefix(absolute(self.plan()?)?)
+                .ok()
+                .map(Path::to_path_buf),
+            None => None,
+        };
+
+        // Listings are separated by NUL so that git doesn't quote the paths
+        Ok(listing
+            .split_terminator('\0')
+            .filter_map(|file| {
+                if repo.is_file {
+                    let name = Path::new(file).file_name()?.to_string_lossy().to_string();
+                    return Some((name, file));
+                }
+
+                let name = Path::new(file).strip_prefix(&repo.relative).ok()?;
+
+                // Same as listing the folder, without the `.odsignore` files
+                let hidden = name.components().any(
+                    |c| matches!(c, Component::Normal(c) if c.to_string_lossy().starts_with('.')),
+                );
+
+                let is_output = name.starts_with(DEFAULT_OUTPUT)
+                    || exclude_dir
+                        .as_ref()
+                        .is_some_and(|dir| name.starts_with(dir));
+
+                if hidden || is_output || !is_selected(name) {
+                    trace!("Skipping file: {}", name.display());
+                    return None;
+                }
+
+                Some((name.to_string_lossy().to_string(), file))
+            })
+            .collect())
+    }
+
+    /// Load the plan as it was at a git revision
+    #[instrument(name = "load_at", skip(self))]
+    pub(crate) fn load_at(&self, rev: &str) -> Result<IndexMap<String, Spec>> {
+        let repo = self.repo()?;
+        let listing = repo.git(&[
+            "ls-tree",
+            "-r",
+            "-z",
+            "--name-only",
+            rev,
+            "--",
+            repo.pathspec(),
+        ])?;
+        let names = self.plan_names(&repo, &listing)?;
+
+        if names.is_empty() {
+            return Err(eyre!("unable to find {} at {rev}", repo.relative));
+        }
+
+        let files = names
+            .into_par_iter()
+            .map(|(name, file)| {
+                trace!("Loading file: {}", name);
+                let content = repo.git(&["show", &format!("{rev}:{file}")])?;
+
+                Ok((name, Spec::parse(Path::new(file), &content)?))
+            })
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .collect::<IndexMap<_, _>>();
+
+        debug!("Loaded {} files", files.len());
+        Ok(files)
+    }
+
+    /// Plan files which differ from a git revision, including untracked ones
+    #[instrument(name = "changed_since", skip(self))]
+    pub(crate) fn changed_since(&self, rev: &str) -> Result<IndexMap<String, ChangedFile>> {
+        let repo = self.repo()?;
+        let pathspec = repo.pathspec();
+
+        let mut listing = repo.git(&[
+            "diff",
+            "-z",
+            "--name-only",
+            "--no-renames",
+            rev,
+            "--",
+            pathspec,
+        ])?;
+        listing.push_str(&repo.git(&[
+            "ls-files",
+            "-z",
+            "--others",
+            "--exclude-standard",
+            "--",
+            pathspec,
+        ])?);
+
+        let existing = repo.git(&["ls-tree", "-r", "-z", "--name-only", rev, "--", pathspec])?;
+        let existing = existing.split_terminator('\0').collect::<Vec<_>>();
+
+        let files = self
+            .plan_names(&repo, &listing)?
+            .into_iter()
+            .map(|(name, file)| {
+                let old = if existing.contains(&file) {
+                    Some(repo.git(&["show", &format!("{rev}:{file}")])?)
+                } else {
+                    None
+                };
+
+                let path = repo.root.join(file);
+
+                Ok((name, ChangedFile { path, old }))
+            })
+            .collect::<Result<IndexMap<_, _>>>()?;
+
+        debug!("Found {} changed files", files.len());
+        Ok(files)
+    }
+}
+
+/// Find the root of the repository containing the path, along with the path
+/// relative to it in the form git uses
+fn repo_path(path: &Path) -> Result<(PathBuf, String)> {
+    let dir = if path.is_file() {
+        path.parent()
+            .ok_or(eyre!("unable to find the folder of {}", path.display()))?
+    } else {
+        path
+    };
+
+    let root = PathBuf::from(git(dir, &["rev-parse", "--show-toplevel"])?.trim()).canonicalize()?;
+
+    let relative = path
+        .strip_prefix(&root)?
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+
+    Ok((root, relative))
+}
+
+/// Run git in the folder, returning its output
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .map_err(|e| eyre!("unable to run git: {e}"))?;
+
+    if !output.status.success() {
+        return Err(eyre!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(String::from_utf8(output.stdout)?)
+}
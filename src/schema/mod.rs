@@ -2,61 +2,241 @@ use std::path::{Path, PathBuf, absolute};
 
 use clap::Parser;
 use eyre::eyre;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use ignore::WalkBuilder;
 use indexmap::IndexMap;
+use rayon::prelude::*;
 use tracing::{debug, instrument, trace};
 
-use crate::{error::Result, schema::spec::Spec};
+use crate::{
+    config::{CONFIG_FILE, Config},
+    error::Result,
+    schema::spec::Spec,
+};
 
+pub(crate) mod git;
 pub mod spec;
+pub mod strict;
+mod watch;
 
-#[derive(Debug, Parser)]
+/// Names of the file which holds the plan-wide lint configuration
+pub(crate) const LINTS_FILES: [&str; 3] = ["lints.json", "lints.yaml", "lints.yml"];
+
+/// Folder in the plan where generated code is written by default
+pub(crate) const DEFAULT_OUTPUT: &str = "ods";
+
+/// Name of the file listing gitignore style patterns of files to skip
+const IGNORE_FILE: &str = ".odsignore";
+
+/// Files loaded from a plan folder when no include globs are given
+const DEFAULT_INCLUDE: &str = "*.{yaml,yml,json}";
+
+#[derive(Debug, Clone, Default, Parser)]
 pub struct SchemaOpt {
-    /// Data plan file or folder
-    pub plan: PathBuf,
+    /// Data plan file or folder (defaults to the one in `ods.toml`)
+    pub plan: Option<PathBuf>,
+
+    /// Data plan file or folder, for when a positional path would be taken
+    /// for something else
+    #[clap(long = "plan", value_name = "PLAN", conflicts_with = "plan")]
+    pub plan_flag: Option<PathBuf>,
+
+    /// Reject unknown keys in the plan
+    #[clap(long, overrides_with = "no_strict")]
+    pub strict: bool,
+
+    /// Allow unknown keys in the plan, even when `ods.toml` or the lints file
+    /// enables strict mode
+    #[clap(long, overrides_with = "strict")]
+    pub no_strict: bool,
+
+    /// Only load files in the plan folder matching the glob (defaults to YAML and JSON files)
+    #[clap(long, value_name = "GLOB")]
+    pub include: Vec<String>,
+
+    /// Skip files in the plan folder matching the glob
+    #[clap(long, value_name = "GLOB")]
+    pub exclude: Vec<String>,
+
+    /// Folder in the plan to skip, set to the output by the commands writing
+    /// generated files
+    #[clap(skip)]
+    pub exclude_dir: Option<PathBuf>,
 }
 
 impl SchemaOpt {
+    /// Fill in the options not given on the command line from the config
+    pub(crate) fn apply(&mut self, config: &Config) {
+        if self.plan.is_none() && self.plan_flag.is_none() {
+            self.plan = config.plan.as_ref().map(|plan| config.resolve(plan));
+        }
+
+        if !self.no_strict {
+            self.strict = self.strict || config.strict.unwrap_or_default();
+        }
+
+        if self.include.is_empty() {
+            self.include = config.include.clone();
+        }
+
+        if self.exclude.is_empty() {
+            self.exclude = config.exclude.clone();
+        }
+    }
+
+    pub(crate) fn plan(&self) -> Result<&Path> {
+        match (&self.plan_flag, &self.plan) {
+            (Some(_), Some(_)) => Err(eyre!("plan given both as a path and with --plan")),
+            (Some(plan), None) | (None, Some(plan)) => Ok(plan),
+            (None, None) => Err(eyre!("no plan given and none found in {CONFIG_FILE}")),
+        }
+    }
+
     #[instrument(name = "load", skip_all)]
     pub(crate) fn load(&self) -> Result<IndexMap<String, Spec>> {
-        let mut files = IndexMap::new();
-        let base_path = absolute(&self.plan)?;
-
-        if self.plan.is_file() {
-            trace!("Loading plan from file");
-            files.insert(
-                self.plan.file_name().unwrap().to_string_lossy().to_string(),
-                Spec::load(&self.plan)?,
-            );
-        } else if self.plan.is_dir() {
-            trace!("Loading plan from folder");
-            load_dir(&mut files, &self.plan, &base_path)?;
-        } else {
-            return Err(eyre!("unable to find {}", self.plan.display()));
+        // Parse in parallel while keeping the order of the listing
+        let files = self
+            .files()?
+            .into_par_iter()
+            .map(|(name, path)| {
+                trace!("Loading file: {}", name);
+                Ok((name, Spec::load(&path)?))
+            })
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .collect::<IndexMap<_, _>>();
+
+        // Other files would silently leave strict mode off
+        for (name, spec) in &files {
+            if spec.strict.is_some() && !LINTS_FILES.contains(&name.as_str()) {
+                return Err(eyre!(
+                    "{name}: `strict` is only read from the lints file ({})",
+                    LINTS_FILES.join(", ")
+                ));
+            }
         }
 
         debug!("Loaded {} files", files.len());
         Ok(files)
     }
-}
 
-fn load_dir(files: &mut IndexMap<String, Spec>, path: &Path, base_path: &PathBuf) -> Result<()> {
-    for entry in path.read_dir()? {
-        let path = absolute(entry?.path())?;
+    /// List the files in the plan keyed by their path relative to the plan,
+    /// sorted so that the output does not depend on the file system
+    pub(crate) fn files(&self) -> Result<Vec<(String, PathBuf)>> {
+        let mut files = vec![];
+        let plan = self.plan()?;
+        let base_path = absolute(plan)?;
 
-        if path.is_file() {
-            let relative_path = path
-                .strip_prefix(base_path)
-                .unwrap()
-                .to_string_lossy()
-                .into();
+        if plan.is_file() {
+            trace!("Listing plan from file");
+            files.push((
+                plan.file_name().unwrap().to_string_lossy().to_string(),
+                base_path,
+            ));
+        } else if plan.is_dir() {
+            trace!("Listing plan from folder");
+            self.list_dir(&mut files, &base_path)?;
+        } else {
+            return Err(eyre!("unable to find {}", plan.display()));
+        }
 
-            trace!("Loading file: {}", relative_path);
-            files.insert(relative_path, Spec::load(&path)?);
+        Ok(files)
+    }
+
+    /// Walk the plan folder, skipping hidden files, the output folders, files
+    /// matched by `.odsignore` and the ones filtered by the globs
+    fn list_dir(&self, files: &mut Vec<(String, PathBuf)>, base_path: &Path) -> Result<()> {
+        let is_selected = self.matcher()?;
+
+        let output = base_path.join(DEFAULT_OUTPUT);
+        let exclude_dir = self.exclude_dir.as_deref().map(absolute).transpose()?;
+
+        let walker = WalkBuilder::new(base_path)
+            .standard_filters(false)
+            .hidden(true)
+            .follow_links(true)
+            .add_custom_ignore_filename(IGNORE_FILE)
+            .sort_by_file_name(|a, b| a.cmp(b))
+            .filter_entry(move |entry| {
+                entry.path() != output && Some(entry.path()) != exclude_dir.as_deref()
+            })
+            .build();
+
+        for entry in walker {
+            let entry = entry?;
+            let path = entry.path();
+
+            if !path.is_file() {
+                continue;
+            }
+
+            let relative_path = path.strip_prefix(base_path)?;
+
+            if !is_selected(relative_path) {
+                trace!("Skipping file: {}", relative_path.display());
+                continue;
+            }
+
+            files.push((
+                relative_path.to_string_lossy().to_string(),
+                path.to_path_buf(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Build the filter over paths relative to the plan folder from the globs
+    fn matcher(&self) -> Result<impl Fn(&Path) -> bool + use<>> {
+        let include = if self.include.is_empty() {
+            glob_set(&[DEFAULT_INCLUDE])?
         } else {
-            trace!("Loading folder: {}", path.to_string_lossy());
-            load_dir(files, &path, base_path)?;
+            glob_set(&self.include)?
+        };
+        let exclude = glob_set(&self.exclude)?;
+
+        Ok(move |path: &Path| include.is_match(path) && !exclude.is_match(path))
+    }
+
+    /// Whether strict mode is enabled either by flag or by the lints file,
+    /// unless turned off on the command line
+    pub(crate) fn is_strict(&self, files: &IndexMap<String, Spec>) -> bool {
+        !self.no_strict
+            && (self.strict
+                || lints_file(files)
+                    .and_then(|spec| spec.strict)
+                    .unwrap_or_default())
+    }
+
+    /// Fail if strict mode is enabled and the plan has unknown keys
+    pub(crate) fn check_strict(&self, files: &IndexMap<String, Spec>) -> Result<()> {
+        if !self.is_strict(files) {
+            return Ok(());
         }
+
+        for (name, spec) in files {
+            if let Some(key) = spec.unknown_keys.first() {
+                return Err(eyre!("{name}: `{key}` {}", key.message()));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub(crate) fn lints_file(files: &IndexMap<String, Spec>) -> Option<&Spec> {
+    files
+        .iter()
+        .find(|(name, _)| LINTS_FILES.contains(&name.as_str()))
+        .map(|(_, spec)| spec)
+}
+
+fn glob_set<T: AsRef<str>>(globs: &[T]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+
+    for glob in globs {
+        builder.add(Glob::new(glob.as_ref())?);
     }
 
-    Ok(())
+    Ok(builder.build()?)
 }
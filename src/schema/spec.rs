@@ -1,45 +1,116 @@
-use std::{collections::HashMap, fs::read_to_string, path::PathBuf};
+use std::{
+    collections::HashMap,
+    fs::read_to_string,
+    path::{Path, PathBuf},
+};
 
 use eyre::eyre;
-use serde::Deserialize;
+use indexmap::IndexMap;
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
 
-use crate::{commands::lint::rules::RulesConfig, error::Result};
+use crate::{
+    commands::lint::rules::RulesConfig,
+    error::Result,
+    schema::strict::{UnknownKey, unknown_keys},
+};
 
-#[derive(Debug, Clone, Deserialize)]
+/// Platform or service which sends events
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
 pub struct Source {
+    /// What the source is
     pub description: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+/// Event tracked by the sources
+#[derive(Debug, Default, Deserialize, JsonSchema)]
 pub struct Event {
+    /// When the event is sent
     pub description: Option<String>,
+    /// Sources which send the event
     pub sources: Option<Vec<String>>,
 }
 
-#[derive(Debug, Deserialize)]
+/// Page tracked by the sources
+#[derive(Debug, Default, Deserialize, JsonSchema)]
 pub struct Pageview {
+    /// What the page is
     pub description: Option<String>,
+    /// Sources which send the pageview
     pub sources: Option<Vec<String>>,
 }
 
-#[derive(Debug, Deserialize)]
+/// Open Data Schema plan file
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct Spec {
+    /// Lint rules configuration
     pub lint: Option<RulesConfig>,
-    pub metrics: Option<HashMap<String, Event>>,
-    pub pageviews: Option<HashMap<String, Pageview>>,
-    pub sources: Option<HashMap<String, Source>>,
+    /// Events keyed by name
+    #[serde(default, deserialize_with = "nullable")]
+    #[schemars(with = "Option<HashMap<String, Option<Event>>>")]
+    pub metrics: Option<IndexMap<String, Event>>,
+    /// Pageviews keyed by name
+    #[serde(default, deserialize_with = "nullable")]
+    #[schemars(with = "Option<HashMap<String, Option<Pageview>>>")]
+    pub pageviews: Option<IndexMap<String, Pageview>>,
+    /// Sources keyed by name
+    #[serde(default, deserialize_with = "nullable")]
+    #[schemars(with = "Option<HashMap<String, Option<Source>>>")]
+    pub sources: Option<IndexMap<String, Source>>,
+
+    /// Reject unknown keys in the plan (only read from the lints file)
+    pub strict: Option<bool>,
+
+    #[serde(skip)]
+    pub unknown_keys: Vec<UnknownKey>,
+
+    /// Hash of the file content
+    #[serde(skip)]
+    pub hash: String,
 }
 
 impl Spec {
     pub(crate) fn load(path: &PathBuf) -> Result<Spec> {
-        let file_content = read_to_string(path)?;
+        Self::parse(path, &read_to_string(path)?)
+    }
 
-        let spec = match path.extension().map(|v| v.to_string_lossy()) {
-            Some(v) if v == "yaml" || v == "yml" => serde_yml::from_str(&file_content)?,
-            Some(v) if v == "json" => serde_json::from_str(&file_content)?,
+    /// Parse the content of a plan file, using the path to detect its format
+    pub(crate) fn parse(path: &Path, file_content: &str) -> Result<Spec> {
+        let (mut spec, value): (Spec, Value) = match path.extension().map(|v| v.to_string_lossy()) {
+            Some(v) if v == "yaml" || v == "yml" => (
+                serde_yml::from_str(file_content)?,
+                serde_yml::from_str(file_content)?,
+            ),
+            Some(v) if v == "json" => (
+                serde_json::from_str(file_content)?,
+                serde_json::from_str(file_content)?,
+            ),
             _ => return Err(eyre!("unable to recognize file format")),
         };
 
+        spec.unknown_keys = unknown_keys(&value);
+        spec.hash = blake3::hash(file_content.as_bytes()).to_hex().to_string();
+
         Ok(spec)
     }
 }
+
+/// Items keyed by name, where a name without a value is an empty item as YAML
+/// gives `null` for it
+fn nullable<'de, D, T>(
+    deserializer: D,
+) -> std::result::Result<Option<IndexMap<String, T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + Default,
+{
+    let items = Option::<IndexMap<String, Option<T>>>::deserialize(deserializer)?;
+
+    Ok(items.map(|items| {
+        items
+            .into_iter()
+            .map(|(name, item)| (name, item.unwrap_or_default()))
+            .collect()
+    }))
+}
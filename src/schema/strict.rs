@@ -0,0 +1,148 @@
+use std::fmt::{Display as FmtDisplay, Formatter, Result as FmtResult};
+
+use serde::{
+    Deserialize, Deserializer,
+    de::{Error as DeError, Visitor, value::Error as ValueError},
+    forward_to_deserialize_any,
+};
+use serde_json::{Map, Value};
+use strsim::jaro_winkler;
+
+use crate::{
+    commands::lint::rules::RulesConfig,
+    schema::spec::{Event, Pageview, Source, Spec},
+};
+
+/// Similarity a valid key needs to be suggested for an unknown one
+const SUGGESTION_THRESHOLD: f64 = 0.8;
+
+/// A key in a plan file that does not belong to the schema
+#[derive(Debug, Clone)]
+pub struct UnknownKey {
+    pub path: Vec<String>,
+    pub suggestion: Option<&'static str>,
+}
+
+impl UnknownKey {
+    pub(crate) fn message(&self) -> String {
+        match self.suggestion {
+            Some(suggestion) => format!("key is unknown, did you mean `{suggestion}`?"),
+            None => "key is unknown".to_string(),
+        }
+    }
+}
+
+impl FmtDisplay for UnknownKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}", self.path.join("."))
+    }
+}
+
+/// Find all the keys in the raw spec value that are not recognized
+pub(crate) fn unknown_keys(value: &Value) -> Vec<UnknownKey> {
+    let mut keys = vec![];
+
+    let Some(spec) = value.as_object() else {
+        return keys;
+    };
+
+    check_keys(&mut keys, &[], spec, fields::<Spec>());
+
+    for (key, value) in spec {
+        let Some(entries) = value.as_object() else {
+            continue;
+        };
+
+        match key.as_str() {
+            "lint" => {
+                check_keys(&mut keys, &[key], entries, fields::<RulesConfig>());
+
+                for (rule, config) in entries {
+                    if let (Some(config), Some(rule_fields)) =
+                        (config.as_object(), RulesConfig::rule_fields(rule))
+                    {
+                        check_keys(&mut keys, &[key, rule], config, &rule_fields);
+                    }
+                }
+            }
+            "metrics" | "pageviews" | "sources" => {
+                let item_fields = match key.as_str() {
+                    "metrics" => fields::<Event>(),
+                    "pageviews" => fields::<Pageview>(),
+                    _ => fields::<Source>(),
+                };
+
+                for (name, item) in entries {
+                    if let Some(item) = item.as_object() {
+                        check_keys(&mut keys, &[key, name], item, item_fields);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    keys
+}
+
+fn check_keys(
+    keys: &mut Vec<UnknownKey>,
+    parent: &[&String],
+    map: &Map<String, Value>,
+    valid: &[&'static str],
+) {
+    for key in map.keys() {
+        if valid.contains(&key.as_str()) {
+            continue;
+        }
+
+        let suggestion = valid
+            .iter()
+            .map(|v| (*v, jaro_winkler(key, v)))
+            .filter(|(_, similarity)| *similarity >= SUGGESTION_THRESHOLD)
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(v, _)| v);
+
+        keys.push(UnknownKey {
+            path: parent
+                .iter()
+                .map(|p| p.to_string())
+                .chain([key.clone()])
+                .collect(),
+            suggestion,
+        });
+    }
+}
+
+/// Returns the field names a struct expects when deserializing
+pub(crate) fn fields<'de, T: Deserialize<'de>>() -> &'static [&'static str] {
+    let mut fields: &'static [&'static str] = &[];
+    let _ = T::deserialize(FieldsDeserializer(&mut fields));
+    fields
+}
+
+struct FieldsDeserializer<'a>(&'a mut &'static [&'static str]);
+
+impl<'de> Deserializer<'de> for FieldsDeserializer<'_> {
+    type Error = ValueError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Self::Error> {
+        Err(ValueError::custom("not a struct"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        fields: &'static [&'static str],
+        _: V,
+    ) -> Result<V::Value, Self::Error> {
+        *self.0 = fields;
+        Err(ValueError::custom("fields collected"))
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
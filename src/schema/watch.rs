@@ -0,0 +1,94 @@
+use std::{
+    io::{IsTerminal, stdout},
+    path::{Path, absolute},
+    sync::mpsc::channel,
+    time::Duration,
+};
+
+use anstream::{print, println};
+use notify::{Event, EventKind, RecursiveMode, Watcher, recommended_watcher};
+use owo_colors::OwoColorize;
+use tracing::{debug, trace};
+
+use crate::{
+    error::{Result, print_error},
+    schema::SchemaOpt,
+};
+
+/// Time to wait for more events so that a burst of writes triggers one run
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+impl SchemaOpt {
+    /// Run the callback now and whenever a plan file changes.
+    ///
+    /// Changes under the `ignore` folder are skipped, which keeps the
+    /// generated output from triggering another run.
+    pub(crate) fn watch(&self, ignore: Option<&Path>, mut run: impl FnMut() -> Result) -> Result {
+        let plan = absolute(self.plan()?)?;
+        let ignore = ignore.map(absolute).transpose()?;
+
+        let (tx, rx) = channel();
+        let mut watcher = recommended_watcher(tx)?;
+
+        // Editors usually replace a file on save, so watch its folder instead
+        let folder = if plan.is_file() {
+            plan.parent().unwrap_or(&plan)
+        } else {
+            &plan
+        };
+
+        watcher.watch(folder, RecursiveMode::Recursive)?;
+
+        // Piped output keeps every run instead of escape codes
+        let clear = stdout().is_terminal();
+
+        loop {
+            // Clear the screen and move the cursor to the top
+            if clear {
+                print!("\x1b[2J\x1b[H");
+            }
+
+            if let Err(e) = run() {
+                print_error(&e);
+            }
+
+            println!("{}", "Watching for changes...".dimmed());
+
+            loop {
+                let event = rx.recv()??;
+
+                if is_relevant(&event, &plan, ignore.as_deref()) {
+                    debug!("Detected change in {:?}", event.paths);
+                    break;
+                }
+
+                trace!("Skipping event: {:?}", event);
+            }
+
+            while rx.recv_timeout(DEBOUNCE).is_ok() {}
+        }
+    }
+}
+
+fn is_relevant(event: &Event, plan: &Path, ignore: Option<&Path>) -> bool {
+    if !matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+    ) {
+        return false;
+    }
+
+    event.paths.iter().any(|path| {
+        let is_plan_file = if plan.is_dir() {
+            path.starts_with(plan)
+        } else {
+            path == plan
+        };
+
+        let is_format = path
+            .extension()
+            .is_some_and(|ext| ["yaml", "yml", "json"].contains(&ext.to_string_lossy().as_ref()));
+
+        is_plan_file && is_format && !ignore.is_some_and(|ignore| path.starts_with(ignore))
+    })
+}
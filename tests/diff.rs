@@ -0,0 +1,105 @@
+use std::{
+    env::temp_dir,
+    fs::{copy, create_dir_all, remove_dir_all},
+    path::{Path, PathBuf},
+    process::Command as StdCommand,
+};
+
+use snapbox::{
+    cmd::{Command, cargo_bin},
+    data::Data,
+    utils::current_dir,
+};
+
+fn fp(path: &str) -> PathBuf {
+    current_dir!().join("fixtures").join("diff").join(path)
+}
+
+fn run(dir: &Path, args: &[&str], snapshot: &str, fail: bool) {
+    let snapshot = current_dir!().join("snapshots").join("diff").join(snapshot);
+
+    let assert = Command::new(cargo_bin!("ods"))
+        .args(["--color", "always", "diff"])
+        .args(args)
+        .current_dir(dir)
+        .assert();
+
+    let assert = if fail {
+        assert.failure()
+    } else {
+        assert.success()
+    };
+
+    assert
+        .stderr_eq(Data::read_from(&snapshot.join("stderr.txt"), None))
+        .stdout_eq(Data::read_from(&snapshot.join("stdout.txt"), None));
+}
+
+fn git(dir: &Path, args: &[&str]) {
+    let status = StdCommand::new("git")
+        .args(["-c", "user.name=ods", "-c", "user.email=ods@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .status()
+        .unwrap();
+
+    assert!(status.success());
+}
+
+#[test]
+fn text() {
+    run(&fp(""), &["old", "new"], "text", false);
+}
+
+#[test]
+fn json() {
+    run(
+        &fp(""),
+        &["old", "new", "--format", "json", "--fail-on-breaking"],
+        "json",
+        true,
+    );
+}
+
+#[test]
+fn unchanged() {
+    run(
+        &fp(""),
+        &["old", "old", "--fail-on-breaking"],
+        "unchanged",
+        false,
+    );
+}
+
+#[test]
+fn revision() {
+    let dir = temp_dir().join("ods-diff-test");
+    let _ = remove_dir_all(&dir);
+    create_dir_all(&dir).unwrap();
+
+    git(&dir, &["init", "--quiet"]);
+    copy(fp("old").join("plan.yaml"), dir.join("plan.yaml")).unwrap();
+    git(&dir, &["add", "plan.yaml"]);
+    git(&dir, &["commit", "--quiet", "--message", "Add plan"]);
+    copy(fp("new").join("plan.yaml"), dir.join("plan.yaml")).unwrap();
+
+    // Same changes as comparing the folders
+    run(&dir, &["HEAD", "plan.yaml"], "text", false);
+}
+
+/// Paths which git would quote are still found at the revision
+#[test]
+fn revision_non_ascii() {
+    let dir = temp_dir().join("ods-diff-non-ascii");
+    let plan = dir.join("plan");
+    let _ = remove_dir_all(&dir);
+    create_dir_all(&plan).unwrap();
+
+    git(&dir, &["init", "--quiet"]);
+    copy(fp("old").join("plan.yaml"), plan.join("événements.yaml")).unwrap();
+    git(&dir, &["add", "."]);
+    git(&dir, &["commit", "--quiet", "--message", "Add plan"]);
+    copy(fp("new").join("plan.yaml"), plan.join("événements.yaml")).unwrap();
+
+    run(&dir, &["HEAD", "plan"], "text", false);
+}
@@ -0,0 +1,84 @@
+use std::{
+    env::temp_dir,
+    fs::{copy, create_dir_all, remove_dir_all},
+    path::PathBuf,
+};
+
+use snapbox::{
+    assert_data_eq,
+    cmd::{Command, cargo_bin},
+    data::Data,
+    utils::current_dir,
+};
+
+const FILES: [&str; 2] = ["metrics.yaml", "sources.json"];
+
+/// Copy the fixture plan to a temporary folder so that it can be rewritten
+fn copy_plan(name: &str) -> PathBuf {
+    let dir = temp_dir().join(name);
+    let _ = remove_dir_all(&dir);
+    create_dir_all(&dir).unwrap();
+
+    for file in FILES {
+        copy(
+            current_dir!().join("fixtures").join("fmt").join(file),
+            dir.join(file),
+        )
+        .unwrap();
+    }
+
+    dir
+}
+
+fn run_on_plan(name: &str, args: &[&str]) {
+    let plan = copy_plan(&format!("ods-fmt-{name}"));
+    let snapshot = current_dir!().join("snapshots").join("fmt").join(name);
+
+    Command::new(cargo_bin!("ods"))
+        .args(["--color", "always", "fmt"])
+        .arg(&plan)
+        .args(args)
+        .assert()
+        .success()
+        .stderr_eq(Data::read_from(&snapshot.join("stderr.txt"), None))
+        .stdout_eq(Data::read_from(&snapshot.join("stdout.txt"), None));
+
+    for file in FILES {
+        assert_data_eq!(
+            Data::read_from(&plan.join(file), None),
+            Data::read_from(&snapshot.join(file), None)
+        );
+    }
+
+    // Formatting is stable
+    Command::new(cargo_bin!("ods"))
+        .args(["fmt", "--check"])
+        .arg(&plan)
+        .args(args)
+        .assert()
+        .success()
+        .stdout_eq("");
+}
+
+#[test]
+fn check() {
+    let snapshot = current_dir!().join("snapshots").join("fmt").join("check");
+
+    Command::new(cargo_bin!("ods"))
+        .args(["--color", "always", "fmt", "--check"])
+        .arg(current_dir!().join("fixtures").join("fmt"))
+        .assert()
+        .failure()
+        .stderr_eq(Data::read_from(&snapshot.join("stderr.txt"), None))
+        .stdout_eq(Data::read_from(&snapshot.join("stdout.txt"), None));
+}
+
+#[test]
+fn format() {
+    run_on_plan("format", &[]);
+}
+
+#[test]
+fn sort() {
+    run_on_plan("sort", &["--sort"]);
+}
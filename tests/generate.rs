@@ -1,4 +1,14 @@
+use std::{
+    env::temp_dir,
+    fs::{copy, create_dir_all, read_dir, read_to_string, remove_dir_all, write},
+    path::{Path, PathBuf},
+    process::Command as StdCommand,
+    thread::sleep,
+    time::{Duration, Instant},
+};
+
 use snapbox::{
+    Assert,
     cmd::{Command, cargo_bin},
     data::Data,
     utils::current_dir,
@@ -44,3 +54,655 @@ fn basic() {
 fn folder() {
     run_on_fixture("folder", &[], false);
 }
+
+#[test]
+fn strict() {
+    run_on_fixture("strict.yaml", &["--strict"], true);
+}
+
+/// Copy a fixture plan into a temporary folder, for the tests writing into the
+/// plan folder
+fn copy_fixture(path: &str, name: &str) -> PathBuf {
+    fn copy_dir(from: &Path, to: &Path) {
+        create_dir_all(to).unwrap();
+
+        for entry in read_dir(from).unwrap() {
+            let path = entry.unwrap().path();
+            let target = to.join(path.file_name().unwrap());
+
+            if path.is_dir() {
+                copy_dir(&path, &target);
+            } else {
+                copy(&path, target).unwrap();
+            }
+        }
+    }
+
+    let plan = temp_dir().join(format!("ods-generate-{name}"));
+    let _ = remove_dir_all(&plan);
+
+    copy_dir(
+        &current_dir!().join("fixtures").join("generate").join(path),
+        &plan,
+    );
+
+    plan
+}
+
+/// Generate the `markdown` fixture into a temporary folder and compare it with
+/// the snapshot folder
+fn run_output(args: &[&str], snapshot: &str) {
+    run_output_on("markdown", args, snapshot);
+}
+
+/// Generate a fixture into a temporary folder and compare it with the snapshot
+/// folder
+fn run_output_on(fixture: &str, args: &[&str], snapshot: &str) {
+    let output = temp_dir().join(format!("ods-generate-{snapshot}"));
+    let _ = remove_dir_all(&output);
+
+    Command::new(cargo_bin!("ods"))
+        .arg("generate")
+        .args(args)
+        .arg(
+            current_dir!()
+                .join("fixtures")
+                .join("generate")
+                .join(fixture),
+        )
+        .arg("-o")
+        .arg(&output)
+        .assert()
+        .success();
+
+    Assert::new().action_env("SNAPSHOTS").subset_eq(
+        current_dir!()
+            .join("snapshots")
+            .join("generate")
+            .join(snapshot),
+        &output,
+    );
+}
+
+/// Files generated into the plan folder are not loaded back as plan files
+#[test]
+fn output_in_plan() {
+    let plan = copy_fixture("markdown", "output-in-plan");
+    let output = plan.join("out");
+
+    for _ in 0..2 {
+        Command::new(cargo_bin!("ods"))
+            .args(["generate", "--strict", "--format", "dbt,segment-protocols"])
+            .arg(&plan)
+            .arg("-o")
+            .arg(&output)
+            .assert()
+            .success();
+    }
+}
+
+#[test]
+fn markdown() {
+    run_output(&["--format", "markdown"], "markdown_file");
+}
+
+#[test]
+fn markdown_source() {
+    run_output(
+        &["--format", "markdown", "--markdown-split", "source"],
+        "markdown_source",
+    );
+}
+
+/// A plan file can't overwrite the index, nor two sources share a page
+#[test]
+fn markdown_collision() {
+    for (fixture, args, error) in [
+        (
+            "readme",
+            &[][..],
+            "`README.yaml` has the same page `README.md` as the index",
+        ),
+        (
+            "duplicate_sources.yaml",
+            &["--markdown-split", "source"][..],
+            "`web-app` has the same page `web-app.md` as another source",
+        ),
+    ] {
+        Command::new(cargo_bin!("ods"))
+            .args(["--color", "never", "generate", "--format", "markdown"])
+            .args(args)
+            .arg(
+                current_dir!()
+                    .join("fixtures")
+                    .join("generate")
+                    .join(fixture),
+            )
+            .arg("-o")
+            .arg(temp_dir().join("ods-generate-markdown-collision"))
+            .assert()
+            .failure()
+            .stderr_eq(format!("error: {error}\n"));
+    }
+}
+
+#[test]
+fn html() {
+    run_output(&["--format", "html"], "html");
+}
+
+#[test]
+fn html_collision() {
+    Command::new(cargo_bin!("ods"))
+        .args(["--color", "never", "generate", "--format", "html"])
+        .arg(
+            current_dir!()
+                .join("fixtures")
+                .join("generate")
+                .join("duplicate_sources.yaml"),
+        )
+        .arg("-o")
+        .arg(temp_dir().join("ods-generate-html-collision"))
+        .assert()
+        .failure()
+        .stderr_eq("error: `web-app` has the same page `sources/web-app.html` as another source\n");
+}
+
+#[test]
+fn vendors() {
+    run_output(&["--format", "segment-protocols,amplitude-csv"], "vendors");
+}
+
+#[test]
+fn iglu() {
+    let previous = current_dir!()
+        .join("fixtures")
+        .join("generate")
+        .join("iglu_previous");
+
+    run_output(
+        &[
+            "--format",
+            "iglu",
+            "--iglu-vendor",
+            "com.acme",
+            "--iglu-previous",
+            previous.to_str().unwrap(),
+        ],
+        "iglu",
+    );
+}
+
+/// A relative `--iglu-previous` is relative to the working folder, not to
+/// `ods.toml`
+#[test]
+fn iglu_previous_relative() {
+    let dir = copy_fixture("iglu_previous", "iglu-previous-relative/previous");
+    let root = dir.parent().unwrap();
+    let work = root.join("work");
+
+    create_dir_all(&work).unwrap();
+    write(
+        root.join("ods.toml"),
+        "[generate.iglu]\nvendor = \"com.acme\"\n",
+    )
+    .unwrap();
+
+    Command::new(cargo_bin!("ods"))
+        .args(["generate", "--format", "iglu"])
+        .args(["--iglu-previous", "../previous", "-o", "out"])
+        .arg(
+            current_dir!()
+                .join("fixtures")
+                .join("generate")
+                .join("markdown"),
+        )
+        .current_dir(&work)
+        .assert()
+        .success();
+
+    Assert::new().action_env("SNAPSHOTS").subset_eq(
+        current_dir!()
+            .join("snapshots")
+            .join("generate")
+            .join("iglu"),
+        work.join("out"),
+    );
+}
+
+#[test]
+fn iglu_duplicate() {
+    Command::new(cargo_bin!("ods"))
+        .args(["--color", "never", "generate", "--format", "iglu"])
+        .args(["--iglu-vendor", "com.acme"])
+        .arg(
+            current_dir!()
+                .join("fixtures")
+                .join("generate")
+                .join("duplicate_names.yaml"),
+        )
+        .arg("-o")
+        .arg(temp_dir().join("ods-generate-iglu-duplicate"))
+        .assert()
+        .failure()
+        .stderr_eq("error: `foo-bar` has the same Iglu schema name `foo_bar` as another event\n");
+}
+
+#[test]
+fn iglu_non_ascii() {
+    Command::new(cargo_bin!("ods"))
+        .args(["--color", "never", "generate", "--format", "iglu"])
+        .args(["--iglu-vendor", "com.acme"])
+        .arg(
+            current_dir!()
+                .join("fixtures")
+                .join("generate")
+                .join("non_ascii.yaml"),
+        )
+        .arg("-o")
+        .arg(temp_dir().join("ods-generate-iglu-non-ascii"))
+        .assert()
+        .failure()
+        .stderr_eq(
+            "error: `Café Opened` doesn't make a valid Iglu schema name, use ASCII letters and digits\n",
+        );
+}
+
+#[test]
+fn sql() {
+    run_output(&["--format", "sql"], "sql_postgres");
+}
+
+#[test]
+fn sql_duplicate() {
+    Command::new(cargo_bin!("ods"))
+        .args(["--color", "never", "generate", "--format", "sql"])
+        .arg(
+            current_dir!()
+                .join("fixtures")
+                .join("generate")
+                .join("duplicate_names.yaml"),
+        )
+        .arg("-o")
+        .arg(temp_dir().join("ods-generate-sql-duplicate"))
+        .assert()
+        .failure()
+        .stderr_eq("error: `foo-bar` has the same table name `foo_bar` as another event\n");
+}
+
+#[test]
+fn sql_dialect() {
+    run_output(
+        &[
+            "--format",
+            "sql",
+            "--sql-dialect",
+            "clickhouse",
+            "--sql-case",
+            "pascal_case",
+        ],
+        "sql_clickhouse",
+    );
+}
+
+#[test]
+fn dbt() {
+    run_output(&["--format", "dbt", "--dbt-schema", "raw_events"], "dbt");
+}
+
+/// Quotes in names don't end the Jinja strings of the staging models
+#[test]
+fn dbt_quotes() {
+    let output = temp_dir().join("ods-generate-dbt-quotes");
+    let _ = remove_dir_all(&output);
+
+    Command::new(cargo_bin!("ods"))
+        .args(["generate", "--format", "dbt", "--dbt-source", "o'brien"])
+        .arg(
+            current_dir!()
+                .join("fixtures")
+                .join("generate")
+                .join("markdown"),
+        )
+        .arg("-o")
+        .arg(&output)
+        .assert()
+        .success();
+
+    let model = read_to_string(
+        output
+            .join("models")
+            .join("staging")
+            .join("stg_o'brien__cart_viewed.sql"),
+    )
+    .unwrap();
+
+    assert!(model.contains("from {{ source('o\\'brien', 'cart_viewed') }}"));
+}
+
+#[test]
+fn dbt_duplicate() {
+    Command::new(cargo_bin!("ods"))
+        .args(["--color", "never", "generate", "--format", "dbt"])
+        .arg(
+            current_dir!()
+                .join("fixtures")
+                .join("generate")
+                .join("duplicate_names.yaml"),
+        )
+        .arg("-o")
+        .arg(temp_dir().join("ods-generate-dbt-duplicate"))
+        .assert()
+        .failure()
+        .stderr_eq("error: `foo-bar` has the same table name `foo_bar` as another event\n");
+}
+
+#[test]
+fn avro() {
+    run_output(
+        &["--format", "avro", "--package", "com.acme.events"],
+        "avro",
+    );
+}
+
+#[test]
+fn avro_non_ascii() {
+    Command::new(cargo_bin!("ods"))
+        .args(["--color", "never", "generate", "--format", "avro"])
+        .arg(current_dir!().join("fixtures").join("generate").join("non_ascii.yaml"))
+        .arg("-o")
+        .arg(temp_dir().join("ods-generate-avro-non-ascii"))
+        .assert()
+        .failure()
+        .stderr_eq("error: `Café Opened` doesn't make a valid Avro record name, use ASCII letters and digits starting with a letter\n");
+}
+
+/// Numbers in an existing lock file are kept, and those of removed events are
+/// reserved. The plan is copied first as the lock file is written next to it.
+#[test]
+fn protobuf_lock() {
+    let plan = copy_fixture("protobuf", "protobuf-lock");
+
+    Command::new(cargo_bin!("ods"))
+        .args(["generate", "--format", "protobuf"])
+        .arg(&plan)
+        .assert()
+        .success();
+
+    Assert::new().action_env("SNAPSHOTS").subset_eq(
+        current_dir!()
+            .join("snapshots")
+            .join("generate")
+            .join("protobuf_lock"),
+        &plan,
+    );
+}
+
+#[test]
+fn python() {
+    run_output(&["--lang", "python"], "python");
+}
+
+/// Quotes and backslashes in descriptions don't end the docstrings
+#[test]
+fn python_quotes() {
+    run_output_on("quotes", &["--lang", "python"], "python_quotes");
+}
+
+#[test]
+fn go() {
+    run_output(&["--lang", "go", "--package", "com.acme.analytics"], "go");
+}
+
+/// Without events nothing uses the `time` package, so it isn't imported
+#[test]
+fn go_empty() {
+    run_output_on("empty.yaml", &["--lang", "go"], "go_empty");
+}
+
+#[test]
+fn go_invalid() {
+    for (fixture, package, error) in [
+        (
+            "duplicate_sources.yaml",
+            "com.acme.analytics",
+            "source `web-app` of `Signed Up` has the same Go constant name `SignedUpSourceWebApp` as another source",
+        ),
+        (
+            "empty.yaml",
+            "com.acme.2024",
+            "`2024` doesn't make a valid Go package name, use ASCII letters and digits starting with a letter",
+        ),
+    ] {
+        Command::new(cargo_bin!("ods"))
+            .args(["--color", "never", "generate", "--lang", "go", "--package"])
+            .arg(package)
+            .arg(
+                current_dir!()
+                    .join("fixtures")
+                    .join("generate")
+                    .join(fixture),
+            )
+            .arg("-o")
+            .arg(temp_dir().join("ods-generate-go-invalid"))
+            .assert()
+            .failure()
+            .stderr_eq(format!("error: {error}\n"));
+    }
+}
+
+#[test]
+fn template() {
+    let template = current_dir!()
+        .join("fixtures")
+        .join("generate")
+        .join("template");
+
+    run_output(
+        &[
+            "--template",
+            template.to_str().unwrap(),
+            "--package",
+            "acme",
+        ],
+        "template",
+    );
+}
+
+/// Events whose names only differ in case can't render to the same path
+#[test]
+fn template_duplicate() {
+    Command::new(cargo_bin!("ods"))
+        .args(["--color", "never", "generate", "--template"])
+        .arg(
+            current_dir!()
+                .join("fixtures")
+                .join("generate")
+                .join("template"),
+        )
+        .arg(
+            current_dir!()
+                .join("fixtures")
+                .join("generate")
+                .join("duplicate_names.yaml"),
+        )
+        .arg("-o")
+        .arg(temp_dir().join("ods-generate-template-duplicate"))
+        .assert()
+        .failure()
+        .stderr_eq(
+            "error: template docs/{{ event.name | kebab_case }}.md.jinja renders to docs/foo-bar.md more than once\n",
+        );
+}
+
+/// Event names can't make a template write outside of the output
+#[test]
+fn template_traversal() {
+    Command::new(cargo_bin!("ods"))
+        .args(["--color", "never", "generate", "--template"])
+        .arg(
+            current_dir!()
+                .join("fixtures")
+                .join("generate")
+                .join("template_raw"),
+        )
+        .arg(
+            current_dir!()
+                .join("fixtures")
+                .join("generate")
+                .join("traversal.yaml"),
+        )
+        .arg("-o")
+        .arg(temp_dir().join("ods-generate-template-traversal").join("out"))
+        .assert()
+        .failure()
+        .stderr_eq(
+            "error: template {{ event.name }}.txt.jinja tried to write ../../escaped.txt outside of the output\n",
+        );
+
+    assert!(!temp_dir().join("escaped.txt").exists());
+}
+
+/// Symlinks to folders in the templates aren't followed, so a loop ends
+#[cfg(unix)]
+#[test]
+fn template_symlink() {
+    let template = temp_dir().join("ods-generate-template-symlink");
+    let _ = remove_dir_all(&template);
+    create_dir_all(&template).unwrap();
+
+    write(template.join("count.txt.jinja"), "{{ events | length }}\n").unwrap();
+    std::os::unix::fs::symlink(&template, template.join("loop")).unwrap();
+
+    let output = temp_dir().join("ods-generate-template-symlink-out");
+
+    Command::new(cargo_bin!("ods"))
+        .args(["generate", "--template"])
+        .arg(&template)
+        .arg(
+            current_dir!()
+                .join("fixtures")
+                .join("generate")
+                .join("markdown"),
+        )
+        .arg("-o")
+        .arg(&output)
+        .assert()
+        .success();
+
+    assert!(output.join("count.txt").is_file());
+    assert!(!output.join("loop").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn plugin() {
+    let plugin = current_dir!()
+        .join("fixtures")
+        .join("generate")
+        .join("plugin")
+        .join("ods-gen-echo");
+
+    run_output(
+        &[
+            "--plugin",
+            plugin.to_str().unwrap(),
+            "--plugin-opt",
+            "target=es2020",
+        ],
+        "plugin",
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn plugin_escape() {
+    let plugin = current_dir!()
+        .join("fixtures")
+        .join("generate")
+        .join("plugin")
+        .join("ods-gen-escape");
+
+    let _ = remove_dir_all(temp_dir().join("ods-generate-plugin-escape"));
+
+    Command::new(cargo_bin!("ods"))
+        .args(["--color", "never", "generate", "--plugin"])
+        .arg(plugin)
+        .arg(
+            current_dir!()
+                .join("fixtures")
+                .join("generate")
+                .join("markdown"),
+        )
+        .args([
+            "-o",
+            temp_dir()
+                .join("ods-generate-plugin-escape")
+                .to_str()
+                .unwrap(),
+        ])
+        .assert()
+        .failure()
+        .stderr_eq(
+            "error: plugin ods-gen-escape tried to write ../escaped.txt outside of the output\n",
+        );
+
+    // Nothing is written when any of the paths is outside of the output
+    assert!(
+        !temp_dir()
+            .join("ods-generate-plugin-escape")
+            .join("inside.txt")
+            .exists()
+    );
+}
+
+/// Plugins replying before reading the plan don't block on a large one
+#[cfg(unix)]
+#[test]
+fn plugin_eager() {
+    let plugin = current_dir!()
+        .join("fixtures")
+        .join("generate")
+        .join("plugin")
+        .join("ods-gen-eager");
+
+    let dir = temp_dir().join("ods-generate-plugin-eager");
+    let _ = remove_dir_all(&dir);
+    create_dir_all(&dir).unwrap();
+
+    let metrics = (0..2000)
+        .map(|i| format!("  Metric {i}:\n    description: {}\n", "a".repeat(100)))
+        .collect::<String>();
+    write(dir.join("plan.yaml"), format!("metrics:\n{metrics}")).unwrap();
+
+    let mut child = StdCommand::new(cargo_bin!("ods"))
+        .args(["generate", "--plugin"])
+        .arg(plugin)
+        .arg(dir.join("plan.yaml"))
+        .arg("-o")
+        .arg(dir.join("out"))
+        .spawn()
+        .unwrap();
+
+    let start = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait().unwrap() {
+            break status;
+        }
+
+        if start.elapsed() > Duration::from_secs(30) {
+            child.kill().unwrap();
+            panic!("plugin blocked");
+        }
+
+        sleep(Duration::from_millis(50));
+    };
+
+    assert!(status.success());
+    assert_eq!(
+        read_to_string(dir.join("out").join("eager.txt"))
+            .unwrap()
+            .len(),
+        200_000
+    );
+}
@@ -0,0 +1,173 @@
+use std::{
+    env::temp_dir,
+    fs::{create_dir_all, read_dir, read_to_string, remove_dir_all},
+};
+
+use snapbox::{
+    Assert,
+    cmd::{Command, cargo_bin},
+    data::Data,
+    utils::current_dir,
+};
+
+/// Import the fixture into a temporary folder and compare the output with the
+/// `plan` folder of the snapshot
+fn run(tool: &str, fixture: &str, args: &[&str], snapshot: &str) {
+    let fixture = current_dir!()
+        .join("fixtures")
+        .join("import")
+        .join(tool)
+        .join(fixture);
+    let snapshot = current_dir!()
+        .join("snapshots")
+        .join("import")
+        .join(tool)
+        .join(snapshot);
+
+    let dir =
+        temp_dir().join(format!("ods-import-{tool}-{}", snapshot.display()).replace('/', "-"));
+    let _ = remove_dir_all(&dir);
+    create_dir_all(&dir).unwrap();
+
+    Command::new(cargo_bin!("ods"))
+        .args(["--color", "always", "import", tool])
+        .arg(fixture)
+        .args(args)
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stderr_eq(Data::read_from(&snapshot.join("stderr.txt"), None))
+        .stdout_eq(Data::read_from(&snapshot.join("stdout.txt"), None));
+
+    Assert::new()
+        .action_env("SNAPSHOTS")
+        .subset_eq(snapshot.join("plan"), dir.join("plan"));
+}
+
+#[test]
+fn segment_public_api() {
+    run("segment", "public_api.json", &[], "public_api");
+}
+
+#[test]
+fn segment_config_api() {
+    run("segment", "config_api.json", &[], "config_api");
+}
+
+#[test]
+fn amplitude() {
+    run("amplitude", "taxonomy.csv", &[], "taxonomy");
+}
+
+#[test]
+fn mixpanel() {
+    run("mixpanel", "lexicon.csv", &[], "lexicon");
+}
+
+/// Import a fixture naming the same event twice, which must fail without
+/// writing anything
+fn duplicate(tool: &str, fixture: &str) {
+    let dir = temp_dir().join(format!("ods-import-{tool}-duplicate"));
+    let _ = remove_dir_all(&dir);
+    create_dir_all(&dir).unwrap();
+
+    Command::new(cargo_bin!("ods"))
+        .args(["--color", "never", "import", tool])
+        .arg(
+            current_dir!()
+                .join("fixtures")
+                .join("import")
+                .join(tool)
+                .join(fixture),
+        )
+        .current_dir(&dir)
+        .assert()
+        .failure()
+        .stderr_eq("error: metric `Order Completed` is defined more than once in the export\n");
+
+    assert!(!dir.join("plan").exists());
+}
+
+#[test]
+fn segment_duplicate() {
+    duplicate("segment", "duplicate.json");
+}
+
+#[test]
+fn amplitude_duplicate() {
+    duplicate("amplitude", "duplicate.csv");
+}
+
+/// Import the fixture, export it back to the tool's format and import that,
+/// which should give the same plan files. Source descriptions are made up by
+/// the importers, so `sources.yaml` is left out.
+fn round_trip(tool: &str, fixture: &str, format: &str, exported: &str) {
+    let fixture = current_dir!()
+        .join("fixtures")
+        .join("import")
+        .join(tool)
+        .join(fixture);
+
+    let dir = temp_dir().join(format!("ods-round-trip-{tool}"));
+    let _ = remove_dir_all(&dir);
+    create_dir_all(&dir).unwrap();
+
+    let ods = |args: &[&str]| {
+        Command::new(cargo_bin!("ods"))
+            .args(args)
+            .current_dir(&dir)
+            .assert()
+            .success();
+    };
+
+    ods(&["import", tool, fixture.to_str().unwrap(), "-o", "first"]);
+    ods(&["generate", "first", "-o", "export", "--format", format]);
+    ods(&[
+        "import",
+        tool,
+        &format!("export/{exported}"),
+        "-o",
+        "second",
+    ]);
+
+    let mut compared = 0;
+
+    for entry in read_dir(dir.join("first")).unwrap() {
+        let path = entry.unwrap().path();
+
+        if path.ends_with("sources.yaml") {
+            continue;
+        }
+
+        let second = dir.join("second").join(path.file_name().unwrap());
+        assert_eq!(
+            read_to_string(&path).unwrap(),
+            read_to_string(&second).unwrap(),
+            "{} differs after the round trip",
+            path.display()
+        );
+        compared += 1;
+    }
+
+    assert!(compared > 0);
+}
+
+#[test]
+fn segment_round_trip() {
+    round_trip(
+        "segment",
+        "public_api.json",
+        "segment-protocols",
+        "segment-protocols.json",
+    );
+}
+
+#[test]
+fn amplitude_round_trip() {
+    round_trip(
+        "amplitude",
+        "taxonomy.csv",
+        "amplitude-csv",
+        "amplitude.csv",
+    );
+}
@@ -0,0 +1,58 @@
+use std::{
+    env::temp_dir,
+    fs::{create_dir_all, remove_dir_all},
+};
+
+use snapbox::{
+    assert_data_eq,
+    cmd::{Command, cargo_bin},
+    data::Data,
+    utils::current_dir,
+};
+
+#[test]
+fn init() {
+    let dir = temp_dir().join("ods-init-test");
+    let _ = remove_dir_all(&dir);
+    create_dir_all(&dir).unwrap();
+
+    let snapshot = current_dir!().join("snapshots").join("init");
+
+    Command::new(cargo_bin!("ods"))
+        .args(["--color", "always", "init", "plan"])
+        .args(["--sources", "web,ios", "--case", "snake_case", "--config"])
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stderr_eq(Data::read_from(&snapshot.join("stderr.txt"), None))
+        .stdout_eq(Data::read_from(&snapshot.join("stdout.txt"), None));
+
+    for file in [
+        "plan/sources.yaml",
+        "plan/metrics.yaml",
+        "plan/pageviews.yaml",
+        "plan/lints.yaml",
+        "ods.toml",
+    ] {
+        assert_data_eq!(
+            Data::read_from(&dir.join(file), None),
+            Data::read_from(&snapshot.join(file), None)
+        );
+    }
+
+    // The starter plan is clean and found through the config
+    Command::new(cargo_bin!("ods"))
+        .args(["lint", "--strict"])
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stdout_eq("");
+
+    // Existing files are left alone
+    Command::new(cargo_bin!("ods"))
+        .args(["--color", "always", "init", "plan"])
+        .current_dir(&dir)
+        .assert()
+        .failure()
+        .stderr_eq(Data::read_from(&snapshot.join("exists.txt"), None));
+}
@@ -1,4 +1,11 @@
-use std::path::PathBuf;
+use std::{
+    env::temp_dir,
+    fs::{copy, create_dir_all, read_dir, read_to_string, remove_dir_all, write},
+    path::{Path, PathBuf},
+    process::{Command as StdCommand, Stdio},
+    thread::sleep,
+    time::Duration,
+};
 
 use snapbox::{
     cmd::{Command, cargo_bin},
@@ -100,3 +107,293 @@ fn lints_file_not_selected() {
         true,
     );
 }
+
+#[test]
+fn strict() {
+    run_on_fixture("strict", &[], true);
+}
+
+#[test]
+fn strict_misplaced() {
+    run_on_fixture("strict_misplaced", &[], true);
+}
+
+#[test]
+fn strict_flag() {
+    run_on_fixture("strict_flag", &["--strict"], true);
+}
+
+#[test]
+fn cache() {
+    let cache = std::env::temp_dir().join("ods-lint-cache-test");
+    let _ = std::fs::remove_file(&cache);
+
+    // The second run reuses the results written by the first one
+    for _ in 0..2 {
+        run_on_fixture(
+            "change_levels",
+            &["--cache", "--cache-location", &cache.to_string_lossy()],
+            true,
+        );
+    }
+
+    assert!(cache.exists());
+}
+
+/// Lint a copy of the `change_levels` plan with the cache, returning the
+/// files whose results came from it
+fn cached_files(dir: &Path) -> Vec<String> {
+    let output = Command::new(cargo_bin!("ods"))
+        .args(["--color", "never", "-vv", "lint", "--cache"])
+        .current_dir(dir)
+        .output()
+        .unwrap();
+
+    let mut files = String::from_utf8_lossy(&output.stderr)
+        .lines()
+        .filter_map(|line| line.split_once("Using cached results for file: "))
+        .map(|(_, file)| file.to_string())
+        .collect::<Vec<_>>();
+
+    files.sort();
+    files
+}
+
+#[test]
+fn cache_invalidation() {
+    let dir = temp_dir().join("ods-lint-cache-invalidation");
+    let _ = remove_dir_all(&dir);
+    create_dir_all(&dir).unwrap();
+
+    for entry in read_dir(fp(&["change_levels"], true)).unwrap() {
+        let entry = entry.unwrap();
+        copy(entry.path(), dir.join(entry.file_name())).unwrap();
+    }
+
+    write(dir.join("ods.toml"), "plan = \".\"\n").unwrap();
+
+    assert!(cached_files(&dir).is_empty());
+    assert!(dir.join(".odscache").is_file());
+
+    // Unchanged files are all reused
+    assert_eq!(cached_files(&dir), ["error.yaml", "off.yaml", "warn.yaml"]);
+
+    // Editing a file only invalidates that file
+    let warn = read_to_string(dir.join("warn.yaml")).unwrap();
+    write(dir.join("warn.yaml"), warn.replace("logs out", "signs out")).unwrap();
+
+    assert_eq!(cached_files(&dir), ["error.yaml", "off.yaml"]);
+    assert_eq!(cached_files(&dir), ["error.yaml", "off.yaml", "warn.yaml"]);
+
+    // Changing the rules invalidates every file
+    write(
+        dir.join("ods.toml"),
+        "plan = \".\"\n\n[lint.rules.uses_name_case]\nmetric = \"title_case\"\n",
+    )
+    .unwrap();
+
+    assert!(cached_files(&dir).is_empty());
+}
+
+#[test]
+fn ignore() {
+    run_on_fixture("ignore", &["--exclude", "excluded/**"], false);
+}
+
+#[test]
+fn config() {
+    let snapshot = current_dir!().join("snapshots").join("lint").join("config");
+
+    // Plan, ignore globs, format and rule overrides all come from `ods.toml`
+    Command::new(cargo_bin!("ods"))
+        .args(["--color", "always", "lint"])
+        .current_dir(fp(&["config", "plan"], true))
+        .assert()
+        .success()
+        .stderr_eq(Data::read_from(&snapshot.join("stderr.txt"), None))
+        .stdout_eq(Data::read_from(&snapshot.join("stdout.txt"), None));
+}
+
+/// With the plan in `ods.toml`, `--file` picks the files of the plan to lint
+#[test]
+fn config_files() {
+    Command::new(cargo_bin!("ods"))
+        .args([
+            "--color",
+            "never",
+            "lint",
+            "--file",
+            "events/pageviews.yaml",
+        ])
+        .current_dir(fp(&["config", "plan"], true))
+        .assert()
+        .success()
+        .stdout_eq(Data::read_from(
+            &current_dir!()
+                .join("snapshots")
+                .join("lint")
+                .join("config_files")
+                .join("stdout.txt"),
+            None,
+        ));
+}
+
+/// A path is always the plan, even with the plan in `ods.toml`
+#[test]
+fn config_plan_path() {
+    let output = Command::new(cargo_bin!("ods"))
+        .args(["--color", "never", "lint", "events/pageviews.yaml"])
+        .current_dir(fp(&["config", "plan"], true))
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.contains("\"file\": \"pageviews.yaml\""));
+}
+
+/// Flags turn off what `ods.toml` turns on
+#[test]
+fn config_override() {
+    let run = |args: &[&str]| {
+        Command::new(cargo_bin!("ods"))
+            .args(["--color", "never", "lint"])
+            .args(args)
+            .current_dir(fp(&["config_override", "plan"], true))
+            .assert()
+    };
+
+    let snapshot = current_dir!()
+        .join("snapshots")
+        .join("lint")
+        .join("config_override");
+
+    run(&[])
+        .success()
+        .stdout_eq(Data::read_from(&snapshot.join("strict.txt"), None));
+    run(&["--no-strict"])
+        .success()
+        .stdout_eq(Data::read_from(&snapshot.join("no_strict.txt"), None));
+    run(&["--no-strict", "--fail"])
+        .failure()
+        .stdout_eq(Data::read_from(&snapshot.join("no_strict.txt"), None));
+}
+
+fn git(dir: &Path, args: &[&str]) {
+    let status = StdCommand::new("git")
+        .args(["-c", "user.name=ods", "-c", "user.email=ods@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .status()
+        .unwrap();
+
+    assert!(status.success());
+}
+
+fn copy_files(from: &str, to: &Path) {
+    for entry in read_dir(fp(&["changed_since", from], true)).unwrap() {
+        let entry = entry.unwrap();
+        copy(entry.path(), to.join(entry.file_name())).unwrap();
+    }
+}
+
+#[test]
+fn changed_since() {
+    let dir = temp_dir().join("ods-lint-changed-since-test");
+    let _ = remove_dir_all(&dir);
+    create_dir_all(&dir).unwrap();
+
+    git(&dir, &["init", "--quiet"]);
+    copy_files("before", &dir);
+    git(&dir, &["add", "."]);
+    git(&dir, &["commit", "--quiet", "--message", "Add plan"]);
+    copy_files("after", &dir);
+
+    let snapshot = current_dir!()
+        .join("snapshots")
+        .join("lint")
+        .join("changed_since");
+
+    // Only the changed items are reported, with duplicates found across the plan
+    Command::new(cargo_bin!("ods"))
+        .args(["--color", "always", "lint", ".", "--changed-since", "HEAD"])
+        .current_dir(&dir)
+        .assert()
+        .failure()
+        .stderr_eq(Data::read_from(&snapshot.join("stderr.txt"), None))
+        .stdout_eq(Data::read_from(&snapshot.join("stdout.txt"), None));
+}
+
+/// Paths which git would quote are still found, and compared with the revision
+#[test]
+fn changed_since_non_ascii() {
+    let dir = temp_dir().join("ods-lint-changed-since-non-ascii");
+    let _ = remove_dir_all(&dir);
+    create_dir_all(&dir).unwrap();
+
+    let file = dir.join("événements.yaml");
+    let before = "metrics:\n  User Logged In:\n    description: Logs in\n";
+
+    git(&dir, &["init", "--quiet"]);
+    write(&file, before).unwrap();
+    git(&dir, &["add", "."]);
+    git(&dir, &["commit", "--quiet", "--message", "Add plan"]);
+    write(&file, format!("{before}  User Logged Out:\n")).unwrap();
+
+    let output = Command::new(cargo_bin!("ods"))
+        .args(["--color", "never", "lint", ".", "--changed-since", "HEAD"])
+        .args(["--format", "json"])
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.contains("\"file\": \"événements.yaml\""));
+    assert!(stdout.contains("User Logged Out"));
+    assert!(!stdout.contains("User Logged In"));
+}
+
+/// A burst of writes to a plan file triggers a single run, which only lints
+/// that file again, and piped output isn't cleared
+#[test]
+fn watch() {
+    let dir = temp_dir().join("ods-lint-watch");
+    let _ = remove_dir_all(&dir);
+    create_dir_all(&dir).unwrap();
+    copy(fp(&["files", "good.yaml"], true), dir.join("good.yaml")).unwrap();
+    copy(fp(&["files", "bad.yaml"], true), dir.join("bad.yaml")).unwrap();
+
+    let mut child = StdCommand::new(cargo_bin!("ods"))
+        .args(["--color", "always", "-vv", "lint", "--watch"])
+        .arg(&dir)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    sleep(Duration::from_secs(1));
+
+    let content = read_to_string(dir.join("good.yaml")).unwrap();
+    for i in 0..3 {
+        write(dir.join("good.yaml"), format!("{content}# edit {i}\n")).unwrap();
+        sleep(Duration::from_millis(20));
+    }
+
+    sleep(Duration::from_secs(1));
+
+    child.kill().unwrap();
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert_eq!(stdout.matches("Watching for changes...").count(), 2);
+    assert!(!stdout.contains("\x1b[2J"));
+
+    assert_eq!(
+        stderr.matches("cached results for file: bad.yaml").count(),
+        1
+    );
+    assert_eq!(
+        stderr.matches("cached results for file: good.yaml").count(),
+        0
+    );
+}
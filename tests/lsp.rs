@@ -0,0 +1,223 @@
+use std::{
+    env::temp_dir,
+    fs::{create_dir_all, remove_dir_all, write},
+    io::{BufRead, BufReader, Read, Write},
+    process::{ChildStdin, ChildStdout, Command, Stdio},
+};
+
+use serde_json::{Value, json};
+use snapbox::{cmd::cargo_bin, utils::current_dir};
+
+fn send(stdin: &mut ChildStdin, message: Value) {
+    let body = message.to_string();
+
+    write!(stdin, "Content-Length: {}\r\n\r\n{body}", body.len()).unwrap();
+    stdin.flush().unwrap();
+}
+
+fn receive(stdout: &mut BufReader<ChildStdout>) -> Value {
+    let mut length = 0;
+
+    loop {
+        let mut header = String::new();
+        stdout.read_line(&mut header).unwrap();
+
+        match header.trim().strip_prefix("Content-Length: ") {
+            Some(value) => length = value.parse().unwrap(),
+            None if header.trim().is_empty() => break,
+            None => {}
+        }
+    }
+
+    let mut body = vec![0; length];
+    stdout.read_exact(&mut body).unwrap();
+
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[test]
+fn diagnostics_and_definition() {
+    let plan = current_dir!()
+        .join("fixtures")
+        .join("lint")
+        .join("rules")
+        .join("needs_explicit_sources");
+
+    let mut child = Command::new(cargo_bin!("ods"))
+        .arg("lsp")
+        .arg(&plan)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+
+    send(
+        &mut stdin,
+        json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {"capabilities": {}}}),
+    );
+    assert!(receive(&mut stdout)["result"]["capabilities"]["renameProvider"] == true);
+
+    send(
+        &mut stdin,
+        json!({"jsonrpc": "2.0", "method": "initialized", "params": {}}),
+    );
+
+    let mut diagnostics = vec![];
+
+    for _ in 0..2 {
+        let notification = receive(&mut stdout);
+        assert_eq!(notification["method"], "textDocument/publishDiagnostics");
+
+        for diagnostic in notification["params"]["diagnostics"].as_array().unwrap() {
+            diagnostics.push((
+                diagnostic["range"]["start"]["line"].as_u64().unwrap(),
+                diagnostic["message"].as_str().unwrap().to_string(),
+            ));
+        }
+    }
+
+    assert_eq!(
+        diagnostics,
+        vec![
+            (1, "source `api` is not defined".to_string()),
+            (5, "source `console` is not defined".to_string()),
+        ]
+    );
+
+    let uri = format!("file://{}", plan.join("basic.yaml").display());
+
+    send(
+        &mut stdin,
+        json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "textDocument/definition",
+            "params": {
+                "textDocument": {"uri": uri},
+                "position": {"line": 2, "character": 20},
+            },
+        }),
+    );
+
+    let definition = receive(&mut stdout);
+    let locations = definition["result"].as_array().unwrap();
+
+    assert_eq!(locations.len(), 1);
+    assert!(
+        locations[0]["uri"]
+            .as_str()
+            .unwrap()
+            .ends_with("sources.yaml")
+    );
+    assert_eq!(locations[0]["range"]["start"]["line"], 1);
+
+    send(
+        &mut stdin,
+        json!({"jsonrpc": "2.0", "id": 3, "method": "shutdown"}),
+    );
+    receive(&mut stdout);
+
+    send(&mut stdin, json!({"jsonrpc": "2.0", "method": "exit"}));
+
+    assert!(child.wait().unwrap().success());
+}
+
+/// Plan files changed outside of the editor update the diagnostics, once the
+/// client watches them for the server
+#[test]
+fn watched_files() {
+    let plan = temp_dir().join("ods-lsp-watched-files");
+    let _ = remove_dir_all(&plan);
+    create_dir_all(&plan).unwrap();
+
+    let metrics = plan.join("metrics.yaml");
+    write(
+        &metrics,
+        "metrics:\n  User Logged In:\n    description: Logs in\n",
+    )
+    .unwrap();
+
+    let mut child = Command::new(cargo_bin!("ods"))
+        .arg("lsp")
+        .arg(&plan)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+
+    send(
+        &mut stdin,
+        json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {
+                "capabilities": {
+                    "workspace": {"didChangeWatchedFiles": {"dynamicRegistration": true}},
+                },
+            },
+        }),
+    );
+    receive(&mut stdout);
+
+    send(
+        &mut stdin,
+        json!({"jsonrpc": "2.0", "method": "initialized", "params": {}}),
+    );
+
+    let request = receive(&mut stdout);
+    assert_eq!(request["method"], "client/registerCapability");
+
+    let registration = &request["params"]["registrations"][0];
+    assert_eq!(registration["method"], "workspace/didChangeWatchedFiles");
+    assert_eq!(
+        registration["registerOptions"]["watchers"][0]["globPattern"],
+        "**/*.{yaml,yml,json}"
+    );
+
+    send(
+        &mut stdin,
+        json!({"jsonrpc": "2.0", "id": request["id"], "result": null}),
+    );
+
+    let notification = receive(&mut stdout);
+    assert_eq!(notification["params"]["diagnostics"], json!([]));
+
+    write(
+        &metrics,
+        "metrics:\n  User Logged In:\n    description: Logs in\n    sources: [api]\n",
+    )
+    .unwrap();
+
+    let uri = format!("file://{}", metrics.display());
+    send(
+        &mut stdin,
+        json!({
+            "jsonrpc": "2.0",
+            "method": "workspace/didChangeWatchedFiles",
+            "params": {"changes": [{"uri": uri, "type": 2}]},
+        }),
+    );
+
+    let notification = receive(&mut stdout);
+    assert_eq!(
+        notification["params"]["diagnostics"][0]["message"],
+        "source `api` is not defined"
+    );
+
+    send(
+        &mut stdin,
+        json!({"jsonrpc": "2.0", "id": 2, "method": "shutdown"}),
+    );
+    receive(&mut stdout);
+
+    send(&mut stdin, json!({"jsonrpc": "2.0", "method": "exit"}));
+
+    assert!(child.wait().unwrap().success());
+}
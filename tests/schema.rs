@@ -0,0 +1,77 @@
+use std::{
+    env::temp_dir,
+    fs::{create_dir_all, remove_dir_all, write},
+};
+
+use serde_json::Value;
+use snapbox::{
+    cmd::{Command, cargo_bin},
+    data::Data,
+    utils::current_dir,
+};
+
+#[test]
+fn schema() {
+    let snapshot = current_dir!().join("snapshots").join("schema");
+
+    Command::new(cargo_bin!("ods"))
+        .args(["--color", "always", "schema"])
+        .assert()
+        .success()
+        .stderr_eq(Data::read_from(&snapshot.join("stderr.txt"), None))
+        .stdout_eq(Data::read_from(&snapshot.join("stdout.txt"), None));
+}
+
+/// Items the schema allows to be `null` are loaded from JSON plans too
+#[test]
+fn nullable_items() {
+    let output = Command::new(cargo_bin!("ods"))
+        .arg("schema")
+        .output()
+        .unwrap();
+    let schema: Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    for key in ["metrics", "pageviews", "sources"] {
+        let items = &schema["properties"][key]["additionalProperties"]["anyOf"];
+
+        assert!(
+            items
+                .as_array()
+                .unwrap()
+                .contains(&serde_json::json!({ "type": "null" })),
+            "{key} items can't be null"
+        );
+    }
+
+    Command::new(cargo_bin!("ods"))
+        .args(["--color", "never", "lint"])
+        .arg(
+            current_dir!()
+                .join("fixtures")
+                .join("schema")
+                .join("nullable.json"),
+        )
+        .assert()
+        .success();
+}
+
+/// Commands which don't read `ods.toml` don't fail on a broken one
+#[test]
+fn broken_config() {
+    let dir = temp_dir().join("ods-schema-broken-config");
+    let _ = remove_dir_all(&dir);
+    create_dir_all(&dir).unwrap();
+    write(dir.join("ods.toml"), "plan = \n").unwrap();
+
+    Command::new(cargo_bin!("ods"))
+        .arg("schema")
+        .current_dir(&dir)
+        .assert()
+        .success();
+
+    Command::new(cargo_bin!("ods"))
+        .arg("lint")
+        .current_dir(&dir)
+        .assert()
+        .failure();
+}
@@ -0,0 +1,90 @@
+use std::{
+    io::{Read, Write},
+    net::TcpStream,
+    process::{Command, Stdio},
+    thread::sleep,
+    time::Duration,
+};
+
+use serde_json::{Value, json};
+use snapbox::{cmd::cargo_bin, utils::current_dir};
+
+const PORT: u16 = 18472;
+
+/// Send a GET request, returning the status line and the body
+fn get(path: &str) -> Option<(String, String)> {
+    let mut stream = TcpStream::connect(("127.0.0.1", PORT)).ok()?;
+
+    write!(
+        stream,
+        "GET {path} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n"
+    )
+    .ok()?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).ok()?;
+
+    let (head, body) = response.split_once("\r\n\r\n")?;
+    let status = head.lines().next()?.to_string();
+
+    Some((status, body.to_string()))
+}
+
+#[test]
+fn explorer_and_api() {
+    let plan = current_dir!()
+        .join("fixtures")
+        .join("generate")
+        .join("markdown");
+
+    let mut child = Command::new(cargo_bin!("ods"))
+        .args(["serve", "--port", &PORT.to_string()])
+        .arg(&plan)
+        .stdout(Stdio::null())
+        .spawn()
+        .unwrap();
+
+    // Wait for the plan to be loaded
+    let mut index = None;
+    for _ in 0..50 {
+        index = get("/").filter(|(status, _)| status.ends_with("200 OK"));
+
+        if index.is_some() {
+            break;
+        }
+
+        sleep(Duration::from_millis(100));
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        let (_, body) = index.expect("server did not start");
+        assert!(body.contains("<title>Data plan</title>"));
+
+        let (status, body) = get("/api/metrics/user-logged-in").unwrap();
+        assert!(status.ends_with("200 OK"));
+        assert_eq!(
+            serde_json::from_str::<Value>(&body).unwrap(),
+            json!({
+                "kind": "metric",
+                "file": "events/metrics.yaml",
+                "name": "User Logged In",
+                "description": "User signs into the application",
+                "sources": ["web", "ios", "android"],
+            })
+        );
+
+        let (_, body) = get("/api/sources").unwrap();
+        let sources = serde_json::from_str::<Value>(&body).unwrap();
+        assert_eq!(sources.as_array().unwrap().len(), 2);
+
+        let (status, _) = get("/api/unknown").unwrap();
+        assert!(status.ends_with("404 Not Found"));
+    });
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    if let Err(e) = result {
+        std::panic::resume_unwind(e);
+    }
+}
@@ -0,0 +1,8 @@
+-- Staging model of the metric Cart | Viewed
+
+select
+    event_id,
+    timestamp,
+    source,
+    user_id
+from {{ source('events', 'cart_viewed') }}
@@ -0,0 +1,8 @@
+-- Staging model of the pageview Home Page
+
+select
+    event_id,
+    timestamp,
+    source,
+    user_id
+from {{ source('events', 'home_page') }}
@@ -0,0 +1,8 @@
+-- Staging model of the pageview Settings Page
+
+select
+    event_id,
+    timestamp,
+    source,
+    user_id
+from {{ source('events', 'settings_page') }}
@@ -0,0 +1,8 @@
+-- Staging model of the metric User Registered
+
+select
+    event_id,
+    timestamp,
+    source,
+    user_id
+from {{ source('events', 'user_registered') }}
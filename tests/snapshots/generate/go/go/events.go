@@ -0,0 +1,146 @@
+// Code generated by ods from the data plan. DO NOT EDIT.
+
+package analytics
+
+import "time"
+
+// Event is any event of the plan
+type Event interface {
+	// EventName is the name of the event in the plan
+	EventName() string
+}
+
+// Tracker sends events, implemented by the analytics client
+type Tracker interface {
+	Track(event Event) error
+}
+
+// UserLoggedInSource is a source which sends UserLoggedIn
+type UserLoggedInSource string
+
+const (
+	UserLoggedInSourceWeb     UserLoggedInSource = "web"
+	UserLoggedInSourceIos     UserLoggedInSource = "ios"
+	UserLoggedInSourceAndroid UserLoggedInSource = "android"
+)
+
+// UserLoggedIn is the metric "User Logged In"
+//
+// User signs into the application
+type UserLoggedIn struct {
+	// Unique identifier of the event
+	EventID string `json:"event_id"`
+
+	// Time the event happened at
+	Timestamp time.Time `json:"timestamp"`
+
+	// Source which sent the event
+	Source UserLoggedInSource `json:"source"`
+
+	// Identifier of the user who triggered the event, if known
+	UserID *string `json:"user_id,omitempty"`
+}
+
+// EventName is the name of the event in the plan
+func (UserLoggedIn) EventName() string { return "User Logged In" }
+
+// UserRegisteredSource is a source which sends UserRegistered
+type UserRegisteredSource string
+
+const (
+	UserRegisteredSourceWeb UserRegisteredSource = "web"
+)
+
+// UserRegistered is the metric "User Registered"
+//
+// User creates an account, either with an email or a
+// single sign-on provider
+type UserRegistered struct {
+	// Unique identifier of the event
+	EventID string `json:"event_id"`
+
+	// Time the event happened at
+	Timestamp time.Time `json:"timestamp"`
+
+	// Source which sent the event
+	Source UserRegisteredSource `json:"source"`
+
+	// Identifier of the user who triggered the event, if known
+	UserID *string `json:"user_id,omitempty"`
+}
+
+// EventName is the name of the event in the plan
+func (UserRegistered) EventName() string { return "User Registered" }
+
+// CartViewed is the metric "Cart | Viewed"
+//
+// User opens the cart
+type CartViewed struct {
+	// Unique identifier of the event
+	EventID string `json:"event_id"`
+
+	// Time the event happened at
+	Timestamp time.Time `json:"timestamp"`
+
+	// Source which sent the event
+	Source string `json:"source"`
+
+	// Identifier of the user who triggered the event, if known
+	UserID *string `json:"user_id,omitempty"`
+}
+
+// EventName is the name of the event in the plan
+func (CartViewed) EventName() string { return "Cart | Viewed" }
+
+// HomePageSource is a source which sends HomePage
+type HomePageSource string
+
+const (
+	HomePageSourceWeb HomePageSource = "web"
+	HomePageSourceIos HomePageSource = "ios"
+)
+
+// HomePage is the pageview "Home Page"
+//
+// Landing page
+type HomePage struct {
+	// Unique identifier of the event
+	EventID string `json:"event_id"`
+
+	// Time the event happened at
+	Timestamp time.Time `json:"timestamp"`
+
+	// Source which sent the event
+	Source HomePageSource `json:"source"`
+
+	// Identifier of the user who triggered the event, if known
+	UserID *string `json:"user_id,omitempty"`
+}
+
+// EventName is the name of the event in the plan
+func (HomePage) EventName() string { return "Home Page" }
+
+// SettingsPageSource is a source which sends SettingsPage
+type SettingsPageSource string
+
+const (
+	SettingsPageSourceIos SettingsPageSource = "ios"
+)
+
+// SettingsPage is the pageview "Settings Page"
+type SettingsPage struct {
+	// Unique identifier of the event
+	EventID string `json:"event_id"`
+
+	// Time the event happened at
+	Timestamp time.Time `json:"timestamp"`
+
+	// Source which sent the event
+	Source SettingsPageSource `json:"source"`
+
+	// Identifier of the user who triggered the event, if known
+	UserID *string `json:"user_id,omitempty"`
+}
+
+// EventName is the name of the event in the plan
+func (SettingsPage) EventName() string { return "Settings Page" }
@@ -0,0 +1,14 @@
+// Code generated by ods from the data plan. DO NOT EDIT.
+
+package events
+
+// Event is any event of the plan
+type Event interface {
+	// EventName is the name of the event in the plan
+	EventName() string
+}
+
+// Tracker sends events, implemented by the analytics client
+type Tracker interface {
+	Track(event Event) error
+}
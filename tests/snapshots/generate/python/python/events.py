@@ -0,0 +1,87 @@
+"""Events of the data plan, generated by ods"""
+
+from dataclasses import asdict, dataclass, field
+from datetime import datetime, timezone
+from typing import ClassVar, Literal, NoReturn, Protocol, TypeAlias
+from uuid import uuid4
+
+
+@dataclass(frozen=True, kw_only=True)
+class BaseEvent:
+    """Fields every event has"""
+
+    name: ClassVar[str]
+    kind: ClassVar[str]
+
+    event_id: str = field(default_factory=lambda: str(uuid4()))
+    """Unique identifier of the event"""
+
+    timestamp: datetime = field(default_factory=lambda: datetime.now(timezone.utc))
+    """Time the event happened at"""
+
+    source: str
+    """Source which sent the event"""
+
+    user_id: str | None = None
+    """Identifier of the user who triggered the event, if known"""
+
+    def to_dict(self) -> dict[str, object]:
+        """Event as JSON-compatible values, keyed by field"""
+        return {
+            "event": self.name,
+            **asdict(self),
+            "timestamp": self.timestamp.isoformat(),
+        }
+
+
+@dataclass(frozen=True, kw_only=True)
+class UserLoggedIn(BaseEvent):
+    """User signs into the application"""
+
+    name: ClassVar[str] = "User Logged In"
+    kind: ClassVar[str] = "metric"
+    source: Literal["web", "ios", "android"]
+
+
+@dataclass(frozen=True, kw_only=True)
+class UserRegistered(BaseEvent):
+    """User creates an account, either with an email or a
+    single sign-on provider"""
+
+    name: ClassVar[str] = "User Registered"
+    kind: ClassVar[str] = "metric"
+    source: Literal["web"]
+
+
+@dataclass(frozen=True, kw_only=True)
+class CartViewed(BaseEvent):
+    """User opens the cart"""
+
+    name: ClassVar[str] = "Cart | Viewed"
+    kind: ClassVar[str] = "metric"
+
+
+@dataclass(frozen=True, kw_only=True)
+class HomePage(BaseEvent):
+    """Landing page"""
+
+    name: ClassVar[str] = "Home Page"
+    kind: ClassVar[str] = "pageview"
+    source: Literal["web", "ios"]
+
+
+@dataclass(frozen=True, kw_only=True)
+class SettingsPage(BaseEvent):
+    name: ClassVar[str] = "Settings Page"
+    kind: ClassVar[str] = "pageview"
+    source: Literal["ios"]
+
+
+Event: TypeAlias = UserLoggedIn | UserRegistered | CartViewed | HomePage | SettingsPage
+"""Any event of the plan"""
+
+
+class Tracker(Protocol):
+    """Sends events, implemented by the analytics client"""
+
+    def track(self, event: Event) -> None: ...
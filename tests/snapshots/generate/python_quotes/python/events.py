@@ -0,0 +1,60 @@
+"""Events of the data plan, generated by ods"""
+
+from dataclasses import asdict, dataclass, field
+from datetime import datetime, timezone
+from typing import ClassVar, Literal, NoReturn, Protocol, TypeAlias
+from uuid import uuid4
+
+
+@dataclass(frozen=True, kw_only=True)
+class BaseEvent:
+    """Fields every event has"""
+
+    name: ClassVar[str]
+    kind: ClassVar[str]
+
+    event_id: str = field(default_factory=lambda: str(uuid4()))
+    """Unique identifier of the event"""
+
+    timestamp: datetime = field(default_factory=lambda: datetime.now(timezone.utc))
+    """Time the event happened at"""
+
+    source: str
+    """Source which sent the event"""
+
+    user_id: str | None = None
+    """Identifier of the user who triggered the event, if known"""
+
+    def to_dict(self) -> dict[str, object]:
+        """Event as JSON-compatible values, keyed by field"""
+        return {
+            "event": self.name,
+            **asdict(self),
+            "timestamp": self.timestamp.isoformat(),
+        }
+
+
+@dataclass(frozen=True, kw_only=True)
+class UserSaidHi(BaseEvent):
+    """User said \"hi\""""
+
+    name: ClassVar[str] = "User Said Hi"
+    kind: ClassVar[str] = "metric"
+
+
+@dataclass(frozen=True, kw_only=True)
+class PathCopied(BaseEvent):
+    """User copied C:\\Users\\ to the \"\"\"clipboard\"\"\""""
+
+    name: ClassVar[str] = "Path Copied"
+    kind: ClassVar[str] = "metric"
+
+
+Event: TypeAlias = UserSaidHi | PathCopied
+"""Any event of the plan"""
+
+
+class Tracker(Protocol):
+    """Sends events, implemented by the analytics client"""
+
+    def track(self, event: Event) -> None: ...
@@ -0,0 +1,53 @@
+-- metric User Logged In
+CREATE TABLE IF NOT EXISTS `UserLoggedIn` (
+  `event_id` UUID,
+  `timestamp` DateTime64(3, 'UTC'),
+  `source` LowCardinality(String),
+  `user_id` Nullable(String)
+)
+ENGINE = MergeTree
+ORDER BY (`timestamp`, `event_id`)
+COMMENT 'User signs into the application';
+
+-- metric User Registered
+CREATE TABLE IF NOT EXISTS `UserRegistered` (
+  `event_id` UUID,
+  `timestamp` DateTime64(3, 'UTC'),
+  `source` LowCardinality(String),
+  `user_id` Nullable(String)
+)
+ENGINE = MergeTree
+ORDER BY (`timestamp`, `event_id`)
+COMMENT 'User creates an account, either with an email or a\nsingle sign-on provider';
+
+-- metric Cart | Viewed
+CREATE TABLE IF NOT EXISTS `CartViewed` (
+  `event_id` UUID,
+  `timestamp` DateTime64(3, 'UTC'),
+  `source` LowCardinality(String),
+  `user_id` Nullable(String)
+)
+ENGINE = MergeTree
+ORDER BY (`timestamp`, `event_id`)
+COMMENT 'User opens the cart';
+
+-- pageview Home Page
+CREATE TABLE IF NOT EXISTS `HomePage` (
+  `event_id` UUID,
+  `timestamp` DateTime64(3, 'UTC'),
+  `source` LowCardinality(String),
+  `user_id` Nullable(String)
+)
+ENGINE = MergeTree
+ORDER BY (`timestamp`, `event_id`)
+COMMENT 'Landing page';
+
+-- pageview Settings Page
+CREATE TABLE IF NOT EXISTS `SettingsPage` (
+  `event_id` UUID,
+  `timestamp` DateTime64(3, 'UTC'),
+  `source` LowCardinality(String),
+  `user_id` Nullable(String)
+)
+ENGINE = MergeTree
+ORDER BY (`timestamp`, `event_id`);
@@ -0,0 +1,53 @@
+-- metric User Logged In
+CREATE TABLE IF NOT EXISTS "user_logged_in" (
+  "event_id" UUID NOT NULL,
+  "timestamp" TIMESTAMPTZ NOT NULL,
+  "source" TEXT NOT NULL,
+  "user_id" TEXT,
+  PRIMARY KEY ("event_id"),
+  CHECK ("source" IN ('web', 'ios', 'android'))
+);
+COMMENT ON TABLE "user_logged_in" IS 'User signs into the application';
+
+-- metric User Registered
+CREATE TABLE IF NOT EXISTS "user_registered" (
+  "event_id" UUID NOT NULL,
+  "timestamp" TIMESTAMPTZ NOT NULL,
+  "source" TEXT NOT NULL,
+  "user_id" TEXT,
+  PRIMARY KEY ("event_id"),
+  CHECK ("source" IN ('web'))
+);
+COMMENT ON TABLE "user_registered" IS 'User creates an account, either with an email or a
+single sign-on provider';
+
+-- metric Cart | Viewed
+CREATE TABLE IF NOT EXISTS "cart_viewed" (
+  "event_id" UUID NOT NULL,
+  "timestamp" TIMESTAMPTZ NOT NULL,
+  "source" TEXT NOT NULL,
+  "user_id" TEXT,
+  PRIMARY KEY ("event_id")
+);
+COMMENT ON TABLE "cart_viewed" IS 'User opens the cart';
+
+-- pageview Home Page
+CREATE TABLE IF NOT EXISTS "home_page" (
+  "event_id" UUID NOT NULL,
+  "timestamp" TIMESTAMPTZ NOT NULL,
+  "source" TEXT NOT NULL,
+  "user_id" TEXT,
+  PRIMARY KEY ("event_id"),
+  CHECK ("source" IN ('web', 'ios'))
+);
+COMMENT ON TABLE "home_page" IS 'Landing page';
+
+-- pageview Settings Page
+CREATE TABLE IF NOT EXISTS "settings_page" (
+  "event_id" UUID NOT NULL,
+  "timestamp" TIMESTAMPTZ NOT NULL,
+  "source" TEXT NOT NULL,
+  "user_id" TEXT,
+  PRIMARY KEY ("event_id"),
+  CHECK ("source" IN ('ios'))
+);
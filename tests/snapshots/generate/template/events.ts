@@ -0,0 +1,17 @@
+// Events of acme
+
+export type Source = "web" | "ios";
+
+/** User signs into the application */
+export const USER_LOGGED_IN = "User Logged In";
+/** User creates an account, either with an email or a
+single sign-on provider */
+export const USER_REGISTERED = "User Registered";
+/** User opens the cart */
+export const CART_VIEWED = "Cart | Viewed";
+/** Landing page */
+export const HOME_PAGE = "Home Page";
+export const SETTINGS_PAGE = "Settings Page";
+
+export const METRICS = [USER_LOGGED_IN, USER_REGISTERED, CART_VIEWED];
+export const PAGEVIEWS = [HOME_PAGE, SETTINGS_PAGE];